@@ -0,0 +1,465 @@
+// Batch solving of Lambert problems read from CSV or JSON Lines files
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::csv::csv_field;
+use crate::lambert::{LambertError, LambertOptions, LambertSolution};
+use crate::LambertSolver;
+use crate::Vector3D;
+
+const CSV_RESULT_HEADER: &str = "line,id,status,v1_x,v1_y,v1_z,v2_x,v2_y,v2_z,\
+                                 dv1,dv2,dv,iterations,residual,warning,error";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchFormat {
+    Csv,
+    JsonLines,
+}
+
+impl BatchFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "csv" => Some(Self::Csv),
+            "jsonl" | "ndjson" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+// One Lambert problem of a batch. v_dep and v_arr are the velocities before
+// departure and after arrival, used for the delta-v of the transfer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LambertCase {
+    pub id: String,
+    pub r1: Vector3D,
+    pub r2: Vector3D,
+    pub tof: f64,
+    pub mu: f64,
+    pub v_dep: Vector3D,
+    pub v_arr: Vector3D,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaseError {
+    // row could not be parsed into a case
+    Malformed(String),
+    Solver(LambertError),
+}
+
+impl fmt::Display for CaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Malformed(message) => write!(f, "malformed row, {}", message),
+            Self::Solver(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for CaseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+    pub case: LambertCase,
+    pub solution: LambertSolution,
+}
+
+impl Transfer {
+    pub fn delta_v1(&self) -> f64 {
+        (self.solution.v1 - self.case.v_dep).mag()
+    }
+
+    pub fn delta_v2(&self) -> f64 {
+        (self.case.v_arr - self.solution.v2).mag()
+    }
+
+    pub fn delta_v(&self) -> f64 {
+        self.delta_v1() + self.delta_v2()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseResult {
+    // line of the case in the input file, starting from 1
+    pub line: usize,
+    // case id, or the line number if the row is malformed
+    pub id: String,
+    pub outcome: Result<Transfer, CaseError>,
+}
+
+impl CaseResult {
+    pub fn status(&self) -> &'static str {
+        match self.outcome {
+            Ok(_) => "solved",
+            Err(CaseError::Malformed(_)) => "malformed",
+            Err(CaseError::Solver(_)) => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BatchSummary {
+    pub solved: usize,
+    pub failed: usize,
+    pub malformed: usize,
+}
+
+impl BatchSummary {
+    pub fn from_results(results: &[CaseResult]) -> Self {
+        let mut summary = Self::default();
+        for result in results {
+            match result.outcome {
+                Ok(_) => summary.solved += 1,
+                Err(CaseError::Malformed(_)) => summary.malformed += 1,
+                Err(CaseError::Solver(_)) => summary.failed += 1,
+            }
+        }
+        summary
+    }
+}
+
+impl fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} solved, {} failed, {} malformed",
+            self.solved, self.failed, self.malformed
+        )
+    }
+}
+
+pub fn read_cases<R: BufRead>(
+    reader: R,
+    format: BatchFormat,
+) -> io::Result<Vec<(usize, Result<LambertCase, CaseError>)>> {
+    // parsed cases with their line numbers, skipping blank lines and # comments.
+    // CSV input starts with a header naming the columns.
+    let mut cases = Vec::new();
+    let mut header: Option<Vec<String>> = None;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = i + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let case = match (format, &header) {
+            (BatchFormat::Csv, None) => {
+                header = Some(line.split(',').map(|c| c.trim().to_string()).collect());
+                continue;
+            }
+            (BatchFormat::Csv, Some(header)) => parse_csv_case(header, &line, line_number),
+            (BatchFormat::JsonLines, _) => parse_json_case(&line, line_number),
+        };
+        cases.push((line_number, case.map_err(CaseError::Malformed)));
+    }
+    Ok(cases)
+}
+
+pub fn solve_cases(
+    cases: Vec<(usize, Result<LambertCase, CaseError>)>,
+    options: &LambertOptions,
+) -> Vec<CaseResult> {
+    cases
+        .into_iter()
+        .map(|(line, case)| match case {
+            Ok(case) => CaseResult {
+                line,
+                id: case.id.clone(),
+                outcome: LambertSolver::with_options(case.r1, case.r2, case.tof, case.mu, *options)
+                    .map(|ls| Transfer {
+                        solution: *ls
+                            .get_solution()
+                            .expect("solver was created from a solution"),
+                        case,
+                    })
+                    .map_err(CaseError::Solver),
+            },
+            Err(e) => CaseResult {
+                line,
+                id: line.to_string(),
+                outcome: Err(e),
+            },
+        })
+        .collect()
+}
+
+pub fn write_results<W: Write>(
+    mut writer: W,
+    results: &[CaseResult],
+    format: BatchFormat,
+) -> io::Result<()> {
+    if format == BatchFormat::Csv {
+        writeln!(writer, "{}", CSV_RESULT_HEADER)?;
+    }
+    for result in results {
+        match format {
+            BatchFormat::Csv => writeln!(writer, "{}", csv_result(result))?,
+            BatchFormat::JsonLines => writeln!(writer, "{}", json_result(result))?,
+        }
+    }
+    Ok(())
+}
+
+pub fn run<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    format: BatchFormat,
+    options: &LambertOptions,
+) -> io::Result<Vec<CaseResult>> {
+    // reads, solves and writes all cases, input and results use the same format
+    let results = solve_cases(read_cases(reader, format)?, options);
+    write_results(writer, &results, format)?;
+    Ok(results)
+}
+
+fn parse_csv_case(
+    header: &[String],
+    line: &str,
+    line_number: usize,
+) -> Result<LambertCase, String> {
+    // columns r1_x .. r2_z, tof and mu with optional id and v_dep_x .. v_arr_z,
+    // missing velocities are zero. Fields are split on commas, quoting is not supported.
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() != header.len() {
+        return Err(format!(
+            "expected {} fields, found {}",
+            header.len(),
+            fields.len()
+        ));
+    }
+    let field = |name: &str| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .map(|i| fields[i])
+    };
+    let number = |name: &str| -> Result<f64, String> {
+        let value = field(name).ok_or(format!("missing column {}", name))?;
+        value
+            .parse()
+            .map_err(|_| format!("invalid {} '{}'", name, value))
+    };
+    let optional_number = |name: &str| match field(name) {
+        Some("") | None => Ok(0.0),
+        Some(_) => number(name),
+    };
+
+    Ok(LambertCase {
+        id: match field("id") {
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => line_number.to_string(),
+        },
+        r1: Vector3D::new(number("r1_x")?, number("r1_y")?, number("r1_z")?),
+        r2: Vector3D::new(number("r2_x")?, number("r2_y")?, number("r2_z")?),
+        tof: number("tof")?,
+        mu: number("mu")?,
+        v_dep: Vector3D::new(
+            optional_number("v_dep_x")?,
+            optional_number("v_dep_y")?,
+            optional_number("v_dep_z")?,
+        ),
+        v_arr: Vector3D::new(
+            optional_number("v_arr_x")?,
+            optional_number("v_arr_y")?,
+            optional_number("v_arr_z")?,
+        ),
+    })
+}
+
+fn parse_json_case(line: &str, line_number: usize) -> Result<LambertCase, String> {
+    // {"id": .., "r1": [x, y, z], "r2": [..], "tof": .., "mu": .., "v_dep": [..], "v_arr": [..]}
+    let value: Value = serde_json::from_str(line).map_err(|e| format!("invalid JSON, {}", e))?;
+    let number = |name: &str| {
+        value
+            .get(name)
+            .ok_or(format!("missing field {}", name))?
+            .as_f64()
+            .ok_or(format!("{} is not a number", name))
+    };
+    let vector = |name: &str| match value.get(name) {
+        Some(Value::Array(v)) => match v.iter().map(Value::as_f64).collect::<Option<Vec<_>>>() {
+            Some(c) if c.len() == 3 => Ok(Vector3D::new(c[0], c[1], c[2])),
+            _ => Err(format!("{} must be an array of three numbers", name)),
+        },
+        Some(_) => Err(format!("{} must be an array of three numbers", name)),
+        None => Err(format!("missing field {}", name)),
+    };
+    let optional_vector = |name: &str| match value.get(name) {
+        Some(Value::Null) | None => Ok(Vector3D::default()),
+        Some(_) => vector(name),
+    };
+
+    Ok(LambertCase {
+        id: match value.get("id") {
+            Some(Value::String(id)) => id.clone(),
+            Some(Value::Number(id)) => id.to_string(),
+            _ => line_number.to_string(),
+        },
+        r1: vector("r1")?,
+        r2: vector("r2")?,
+        tof: number("tof")?,
+        mu: number("mu")?,
+        v_dep: optional_vector("v_dep")?,
+        v_arr: optional_vector("v_arr")?,
+    })
+}
+
+fn csv_result(result: &CaseResult) -> String {
+    let mut fields = vec![
+        result.line.to_string(),
+        csv_field(&result.id),
+        result.status().to_string(),
+    ];
+    match &result.outcome {
+        Ok(transfer) => {
+            let s = &transfer.solution;
+            fields.extend(
+                [
+                    s.v1.x,
+                    s.v1.y,
+                    s.v1.z,
+                    s.v2.x,
+                    s.v2.y,
+                    s.v2.z,
+                    transfer.delta_v1(),
+                    transfer.delta_v2(),
+                    transfer.delta_v(),
+                ]
+                .iter()
+                .map(f64::to_string),
+            );
+            fields.push(s.iterations.to_string());
+            fields.push(s.residual.to_string());
+            fields.push(
+                s.warning
+                    .map(|w| csv_field(&w.to_string()))
+                    .unwrap_or_default(),
+            );
+            fields.push(String::new());
+        }
+        Err(e) => {
+            fields.extend(vec![String::new(); 12]);
+            fields.push(csv_field(&e.to_string()));
+        }
+    }
+    fields.join(",")
+}
+
+fn json_result(result: &CaseResult) -> Value {
+    let mut value = json!({
+        "line": result.line,
+        "id": result.id,
+        "status": result.status(),
+    });
+    match &result.outcome {
+        Ok(transfer) => {
+            let s = &transfer.solution;
+            value["v1"] = json!([s.v1.x, s.v1.y, s.v1.z]);
+            value["v2"] = json!([s.v2.x, s.v2.y, s.v2.z]);
+            value["dv1"] = json!(transfer.delta_v1());
+            value["dv2"] = json!(transfer.delta_v2());
+            value["dv"] = json!(transfer.delta_v());
+            value["iterations"] = json!(s.iterations);
+            value["residual"] = json!(s.residual);
+            value["warning"] = json!(s.warning.map(|w| w.to_string()));
+        }
+        Err(e) => value["error"] = json!(e.to_string()),
+    }
+    value
+}
+
+#[cfg(test)]
+#[test]
+fn test_csv_batch() {
+    let input = "\
+# departure and arrival velocities are optional
+id,r1_x,r1_y,r1_z,r2_x,r2_y,r2_z,tof,mu,v_dep_x,v_dep_y,v_dep_z
+vallado,5000,10000,2100,-14600,2500,7000,3600,3.986004e5,-5.9,1.9,3.2
+
+no_velocity,5000,10000,2100,-14600,2500,7000,3600,3.986004e5,,,
+negative_tof,5000,10000,2100,-14600,2500,7000,-1,3.986004e5,,,
+short_row,5000,10000,2100
+bad_number,5000,10000,2100,-14600,2500,x,3600,3.986004e5,,,
+";
+    let mut output = Vec::new();
+    let results = run(
+        input.as_bytes(),
+        &mut output,
+        BatchFormat::Csv,
+        &LambertOptions::default(),
+    )
+    .unwrap();
+    let summary = BatchSummary::from_results(&results);
+    assert_eq!(
+        summary,
+        BatchSummary {
+            solved: 2,
+            failed: 1,
+            malformed: 2
+        }
+    );
+    let lines: Vec<_> = results.iter().map(|r| r.line).collect();
+    assert_eq!(lines, [3, 5, 6, 7, 8]);
+    assert_eq!(results[0].id, "vallado");
+
+    let transfer = results[1].outcome.as_ref().unwrap();
+    assert_eq!(transfer.case.v_dep, Vector3D::default());
+    assert!(
+        (transfer.delta_v() - transfer.solution.v1.mag() - transfer.solution.v2.mag()).abs()
+            < 1e-12
+    );
+    let transfer = results[0].outcome.as_ref().unwrap();
+    assert!(transfer.delta_v1() < 0.2);
+
+    let output = String::from_utf8(output).unwrap();
+    let rows: Vec<_> = output.lines().collect();
+    assert_eq!(rows.len(), 6);
+    assert_eq!(rows[0], CSV_RESULT_HEADER);
+    for row in &rows[1..3] {
+        assert_eq!(row.split(',').count(), 16);
+    }
+    // error messages with commas are quoted
+    assert!(rows[3].starts_with("6,negative_tof,failed,,,,,,,,,,,,,\"invalid input, "));
+    assert!(rows[4].starts_with("7,7,malformed,"));
+}
+
+#[test]
+fn test_json_lines_batch() {
+    let input = r#"{"id": 1, "r1": [5000, 10000, 2100], "r2": [-14600, 2500, 7000], "tof": 3600, "mu": 3.986004e5}
+{"r1": [5000, 10000], "r2": [-14600, 2500, 7000], "tof": 3600, "mu": 3.986004e5}
+{"r1": [5000, 10000, 2100], "r2": [-14600, 2500, 7000], "mu": 3.986004e5}
+not json
+"#;
+    let mut output = Vec::new();
+    let results = run(
+        input.as_bytes(),
+        &mut output,
+        BatchFormat::JsonLines,
+        &LambertOptions::default(),
+    )
+    .unwrap();
+    let statuses: Vec<_> = results.iter().map(CaseResult::status).collect();
+    assert_eq!(statuses, ["solved", "malformed", "malformed", "malformed"]);
+    assert_eq!(results[0].id, "1");
+    assert_eq!(
+        results[2].outcome,
+        Err(CaseError::Malformed("missing field tof".to_string()))
+    );
+
+    let output = String::from_utf8(output).unwrap();
+    let rows: Vec<Value> = output
+        .lines()
+        .map(|row| serde_json::from_str(row).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 4);
+    assert!((rows[0]["v1"][0].as_f64().unwrap() + 5.992494984068112).abs() < 1e-9);
+    assert_eq!(rows[0]["warning"], Value::Null);
+    assert_eq!(rows[1]["line"], 2);
+    assert!(rows[1]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("malformed row"));
+}
@@ -0,0 +1,606 @@
+// Headless Lambert solver: prints the solution and transfer orbit as text or JSON,
+// solves a batch file of cases or writes a porkchop plot grid. Exits with 1 when the
+// solver fails or a batch case fails or is malformed, and 2 on invalid arguments
+// or unreadable files.
+use std::env;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::process;
+
+use orbital::batch::{self, BatchFormat, BatchSummary, CaseError};
+use orbital::ephemeris::Planet;
+use orbital::lambert::{
+    LambertBranch, LambertError, LambertOptions, LambertSolution, TransferDirection,
+};
+use orbital::porkchop::{self, Porkchop, PorkchopNode};
+use orbital::time::{Duration, Epoch};
+use orbital::{LambertSolver, Orbit, Vector3D};
+use serde_json::{json, Value};
+
+const USAGE: &str = "\
+usage: lambert --r1 X,Y,Z --r2 X,Y,Z --tof SECONDS --mu KM3_S2 [options]
+       lambert --r1 X,Y,Z --r2 X,Y,Z --departure DATE --arrival DATE --mu KM3_S2 [options]
+       lambert batch INPUT OUTPUT [options]
+       lambert porkchop OUTPUT --from PLANET --to PLANET --departure START,END
+                        --arrival START,END [--step DAYS] [options]
+
+options:
+    --direction DIR   prograde (default), retrograde, short-way or long-way
+    --revolutions N   full revolutions of the transfer (default 0)
+    --branch BRANCH   left (default) or right solution of a multi-revolution transfer
+    --json            print the result as JSON
+    --departure DATE  epoch at r1 instead of --tof, ISO-8601 such as
+                      2020-07-30T11:50:00Z or \"2020-07-30T11:51:09.184 TDB\"
+    --arrival DATE    epoch at r2
+    --format FORMAT   batch file format, csv or jsonl (default from INPUT extension)
+    --step DAYS       porkchop grid spacing of departure and arrival dates (default 5)
+    -h, --help        print this message";
+
+struct Args {
+    r1: Vector3D,
+    r2: Vector3D,
+    tof: f64,
+    mu: f64,
+    options: LambertOptions,
+    json: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut r1 = None;
+    let mut r2 = None;
+    let mut tof = None;
+    let mut departure = None;
+    let mut arrival = None;
+    let mut mu = None;
+    let mut options = LambertOptions::default();
+    let mut json = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--r1" => r1 = Some(parse_vector("r1", &value()?)?),
+            "--r2" => r2 = Some(parse_vector("r2", &value()?)?),
+            "--tof" => tof = Some(parse_float("tof", &value()?)?),
+            "--departure" => departure = Some(parse_epoch("departure", &value()?)?),
+            "--arrival" => arrival = Some(parse_epoch("arrival", &value()?)?),
+            "--mu" => mu = Some(parse_float("mu", &value()?)?),
+            "--direction" => options.direction = parse_direction(&value()?)?,
+            "--revolutions" => options.revolutions = parse_revolutions(&value()?)?,
+            "--branch" => options.branch = parse_branch(&value()?)?,
+            "--json" => json = true,
+            "-h" | "--help" => return Ok(None),
+            _ => return Err(format!("unknown argument {}", arg)),
+        }
+    }
+
+    let tof = match (tof, departure, arrival) {
+        (Some(tof), None, None) => tof,
+        (None, Some(departure), Some(arrival)) => (arrival - departure).seconds(),
+        (None, None, None) => {
+            return Err("--tof or --departure and --arrival is required".to_string())
+        }
+        (Some(_), _, _) => return Err("--tof cannot be combined with dates".to_string()),
+        _ => return Err("--departure and --arrival must be given together".to_string()),
+    };
+
+    Ok(Some(Args {
+        r1: r1.ok_or("--r1 is required")?,
+        r2: r2.ok_or("--r2 is required")?,
+        tof,
+        mu: mu.ok_or("--mu is required")?,
+        options,
+        json,
+    }))
+}
+
+struct BatchArgs {
+    input: PathBuf,
+    output: PathBuf,
+    format: BatchFormat,
+    options: LambertOptions,
+}
+
+fn parse_batch_args(mut args: impl Iterator<Item = String>) -> Result<Option<BatchArgs>, String> {
+    let mut paths = Vec::new();
+    let mut format = None;
+    let mut options = LambertOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--format" => format = Some(parse_format(&value()?)?),
+            "--direction" => options.direction = parse_direction(&value()?)?,
+            "-h" | "--help" => return Ok(None),
+            _ if arg.starts_with("--") => return Err(format!("unknown argument {}", arg)),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    let mut paths = paths.into_iter();
+    let (input, output) = match (paths.next(), paths.next(), paths.next()) {
+        (Some(input), Some(output), None) => (input, output),
+        _ => return Err("batch needs INPUT and OUTPUT files".to_string()),
+    };
+    let format = match format.or_else(|| BatchFormat::from_path(&input)) {
+        Some(format) => format,
+        None => return Err(format!("cannot tell the format of {}", input.display())),
+    };
+    Ok(Some(BatchArgs {
+        input,
+        output,
+        format,
+        options,
+    }))
+}
+
+struct PorkchopArgs {
+    output: PathBuf,
+    from: Planet,
+    to: Planet,
+    departures: Vec<Epoch>,
+    arrivals: Vec<Epoch>,
+    options: LambertOptions,
+}
+
+fn parse_porkchop_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<Option<PorkchopArgs>, String> {
+    let mut output = None;
+    let mut from = None;
+    let mut to = None;
+    let mut departure = None;
+    let mut arrival = None;
+    let mut step = 5.0;
+    let mut options = LambertOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--from" => from = Some(parse_planet(&value()?)?),
+            "--to" => to = Some(parse_planet(&value()?)?),
+            "--departure" => departure = Some(parse_window("departure", &value()?)?),
+            "--arrival" => arrival = Some(parse_window("arrival", &value()?)?),
+            "--step" => step = parse_float("step", &value()?)?,
+            "--direction" => options.direction = parse_direction(&value()?)?,
+            "-h" | "--help" => return Ok(None),
+            _ if arg.starts_with("--") => return Err(format!("unknown argument {}", arg)),
+            _ if output.is_none() => output = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+
+    if !step.is_finite() || step <= 0.0 {
+        return Err(format!("invalid step '{}', must be positive", step));
+    }
+    let step = Duration::from_days(step);
+    let (departure_start, departure_end) = departure.ok_or("--departure is required")?;
+    let (arrival_start, arrival_end) = arrival.ok_or("--arrival is required")?;
+    Ok(Some(PorkchopArgs {
+        output: output.ok_or("porkchop needs an OUTPUT file")?,
+        from: from.ok_or("--from is required")?,
+        to: to.ok_or("--to is required")?,
+        departures: porkchop::epoch_range(departure_start, departure_end, step),
+        arrivals: porkchop::epoch_range(arrival_start, arrival_end, step),
+        options,
+    }))
+}
+
+fn parse_planet(s: &str) -> Result<Planet, String> {
+    Planet::find(s).ok_or(format!("unknown planet '{}'", s))
+}
+
+fn parse_window(name: &str, s: &str) -> Result<(Epoch, Epoch), String> {
+    match s.split(',').collect::<Vec<_>>()[..] {
+        [start, end] => Ok((parse_epoch(name, start)?, parse_epoch(name, end)?)),
+        _ => Err(format!("{} must be START,END dates", name)),
+    }
+}
+
+fn parse_format(s: &str) -> Result<BatchFormat, String> {
+    match s {
+        "csv" => Ok(BatchFormat::Csv),
+        "jsonl" => Ok(BatchFormat::JsonLines),
+        _ => Err(format!("unknown format '{}'", s)),
+    }
+}
+
+fn parse_float(name: &str, s: &str) -> Result<f64, String> {
+    s.trim()
+        .parse()
+        .map_err(|e| format!("invalid {} '{}': {}", name, s, e))
+}
+
+fn parse_vector(name: &str, s: &str) -> Result<Vector3D, String> {
+    let components = s
+        .split(',')
+        .map(|c| parse_float(name, c))
+        .collect::<Result<Vec<_>, _>>()?;
+    match components[..] {
+        [x, y, z] => Ok(Vector3D::new(x, y, z)),
+        _ => Err(format!(
+            "{} must have three comma separated components",
+            name
+        )),
+    }
+}
+
+fn parse_epoch(name: &str, s: &str) -> Result<Epoch, String> {
+    s.parse()
+        .map_err(|e| format!("invalid {} '{}': {}", name, s, e))
+}
+
+fn parse_direction(s: &str) -> Result<TransferDirection, String> {
+    match s {
+        "prograde" => Ok(TransferDirection::Prograde),
+        "retrograde" => Ok(TransferDirection::Retrograde),
+        "short-way" => Ok(TransferDirection::ShortWay),
+        "long-way" => Ok(TransferDirection::LongWay),
+        _ => Err(format!("unknown direction '{}'", s)),
+    }
+}
+
+fn parse_revolutions(s: &str) -> Result<u32, String> {
+    s.trim()
+        .parse()
+        .map_err(|e| format!("invalid revolutions '{}': {}", s, e))
+}
+
+fn parse_branch(s: &str) -> Result<LambertBranch, String> {
+    match s {
+        "left" => Ok(LambertBranch::Left),
+        "right" => Ok(LambertBranch::Right),
+        _ => Err(format!("unknown branch '{}'", s)),
+    }
+}
+
+fn branch_name(branch: LambertBranch) -> &'static str {
+    match branch {
+        LambertBranch::Left => "left",
+        LambertBranch::Right => "right",
+    }
+}
+
+fn print_text(solution: &LambertSolution, orbit: &Orbit) {
+    let v1 = solution.v1;
+    let v2 = solution.v2;
+    println!(
+        "v1 = ({:.6}, {:.6}, {:.6}) km/s, |v1| = {:.6} km/s",
+        v1.x,
+        v1.y,
+        v1.z,
+        v1.mag()
+    );
+    println!(
+        "v2 = ({:.6}, {:.6}, {:.6}) km/s, |v2| = {:.6} km/s",
+        v2.x,
+        v2.y,
+        v2.z,
+        v2.mag()
+    );
+    println!(
+        "status: converged in {} iterations, time-of-flight residual {:.3e} s",
+        solution.iterations, solution.residual
+    );
+    if let Some(branch) = solution.branch {
+        println!(
+            "{} revolutions, {} branch",
+            solution.revolutions,
+            branch_name(branch)
+        );
+    }
+    if let Some(warning) = solution.warning {
+        println!("warning: {}", warning);
+    }
+    println!("transfer orbit at r2:");
+    println!("    a    = {:.6} km", orbit.a);
+    println!("    ecc  = {:.8}", orbit.ecc);
+    println!("    inc  = {:.6} deg", orbit.inc.to_degrees());
+    println!("    lan  = {:.6} deg", orbit.lan.to_degrees());
+    println!("    argp = {:.6} deg", orbit.argp.to_degrees());
+    println!("    nu   = {:.6} deg", orbit.nu.to_degrees());
+}
+
+fn print_json(solution: &LambertSolution, orbit: &Orbit) {
+    println!("{}", solution_json(solution, orbit));
+}
+
+fn solution_json(solution: &LambertSolution, orbit: &Orbit) -> Value {
+    // non-finite numbers, e.g. the semi-major axis of a parabola, become null
+    let vector = |v: Vector3D| json!([v.x, v.y, v.z]);
+    json!({
+        "status": "solved",
+        "v1": vector(solution.v1),
+        "v2": vector(solution.v2),
+        "revolutions": solution.revolutions,
+        "branch": solution.branch.map(branch_name),
+        "iterations": solution.iterations,
+        "residual": solution.residual,
+        "warning": solution.warning.map(|w| w.to_string()),
+        // angles in degrees as in the text output
+        "orbit": {
+            "a": orbit.a,
+            "ecc": orbit.ecc,
+            "inc_deg": orbit.inc.to_degrees(),
+            "lan_deg": orbit.lan.to_degrees(),
+            "argp_deg": orbit.argp.to_degrees(),
+            "nu_deg": orbit.nu.to_degrees(),
+        },
+    })
+}
+
+fn print_error(error: &LambertError, json: bool) {
+    // on stderr, so that failures are never read as results
+    if json {
+        eprintln!("{}", error_json(error));
+    } else {
+        eprintln!("status: failed, {}", error);
+    }
+}
+
+fn error_json(error: &LambertError) -> Value {
+    json!({"status": "failed", "error": error.to_string()})
+}
+
+fn run_batch(args: BatchArgs) -> Result<BatchSummary, String> {
+    let input = File::open(&args.input)
+        .map_err(|e| format!("cannot open {}: {}", args.input.display(), e))?;
+    let output = File::create(&args.output)
+        .map_err(|e| format!("cannot create {}: {}", args.output.display(), e))?;
+    let results = batch::run(
+        BufReader::new(input),
+        BufWriter::new(output),
+        args.format,
+        &args.options,
+    )
+    .map_err(|e| e.to_string())?;
+
+    for result in &results {
+        if let Err(e @ CaseError::Malformed(_)) = &result.outcome {
+            eprintln!("line {}: {}", result.line, e);
+        }
+    }
+    Ok(BatchSummary::from_results(&results))
+}
+
+fn run_porkchop(args: PorkchopArgs) -> Result<Porkchop, String> {
+    let path = args.output;
+    let output =
+        File::create(&path).map_err(|e| format!("cannot create {}: {}", path.display(), e))?;
+    let porkchop = Porkchop::sweep(
+        args.from,
+        args.departures,
+        args.to,
+        args.arrivals,
+        &args.options,
+    );
+    porkchop
+        .write_csv(BufWriter::new(output))
+        .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    Ok(porkchop)
+}
+
+fn print_best(name: &str, node: Option<&PorkchopNode>) {
+    match node.map(|node| (node, node.outcome.as_ref())) {
+        Some((node, Ok(transfer))) => eprintln!(
+            "{}: departure {}, arrival {}, C3 {:.3} km^2/s^2, dv {:.3} km/s",
+            name,
+            node.departure,
+            node.arrival,
+            transfer.c3(),
+            transfer.delta_v()
+        ),
+        _ => eprintln!("{}: no transfer found", name),
+    }
+}
+
+fn usage_error(e: String) -> ! {
+    eprintln!("error: {}\n\n{}", e, USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("batch") {
+        let args = match parse_batch_args(args.skip(1)) {
+            Ok(Some(args)) => args,
+            Ok(None) => {
+                println!("{}", USAGE);
+                return;
+            }
+            Err(e) => usage_error(e),
+        };
+        match run_batch(args) {
+            Ok(summary) => {
+                eprintln!("{}", summary);
+                if summary.failed + summary.malformed > 0 {
+                    process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(2);
+            }
+        }
+        return;
+    }
+
+    if args.peek().map(String::as_str) == Some("porkchop") {
+        let args = match parse_porkchop_args(args.skip(1)) {
+            Ok(Some(args)) => args,
+            Ok(None) => {
+                println!("{}", USAGE);
+                return;
+            }
+            Err(e) => usage_error(e),
+        };
+        match run_porkchop(args) {
+            Ok(porkchop) => {
+                print_best("minimum C3", porkchop.min_c3());
+                print_best("minimum dv", porkchop.min_delta_v());
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(2);
+            }
+        }
+        return;
+    }
+
+    let args = match parse_args(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(e) => usage_error(e),
+    };
+
+    match LambertSolver::with_options(args.r1, args.r2, args.tof, args.mu, args.options) {
+        Ok(ls) => {
+            let solution = ls
+                .get_solution()
+                .expect("solver was created from a solution");
+            if args.json {
+                print_json(solution, &ls.get_orbit());
+            } else {
+                print_text(solution, &ls.get_orbit());
+            }
+        }
+        Err(e) => {
+            print_error(&e, args.json);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_parse_args() {
+    let args = |s: &str| parse_args(s.split_whitespace().map(String::from));
+    let parsed = args("--r1 1,2,3 --r2 4,5,6 --tof 60 --mu 1e5 --direction long-way --json")
+        .unwrap()
+        .unwrap();
+    assert_eq!(parsed.r1, Vector3D::new(1.0, 2.0, 3.0));
+    assert_eq!(parsed.r2, Vector3D::new(4.0, 5.0, 6.0));
+    assert_eq!(parsed.tof, 60.0);
+    assert_eq!(parsed.mu, 1e5);
+    assert_eq!(parsed.options.direction, TransferDirection::LongWay);
+    assert!(parsed.json);
+
+    assert!(args("--help").unwrap().is_none());
+    assert!(args("--r1 1,2 --r2 4,5,6 --tof 60 --mu 1e5").is_err());
+    assert!(args("--r1 1,2,3 --r2 4,5,6 --tof 60").is_err());
+    assert!(args("--r1 1,2,3 --r2 4,5,6 --tof 60 --mu").is_err());
+    assert!(args("--r1 1,2,3 --r2 4,5,6 --tof 60 --mu 1e5 --direction up").is_err());
+
+    let parsed = args("--r1 1,2,3 --r2 4,5,6 --tof 60 --mu 1e5 --revolutions 2 --branch right")
+        .unwrap()
+        .unwrap();
+    assert_eq!(parsed.options.revolutions, 2);
+    assert_eq!(parsed.options.branch, LambertBranch::Right);
+    assert!(args("--r1 1,2,3 --r2 4,5,6 --tof 60 --mu 1e5 --revolutions -1").is_err());
+    assert!(args("--r1 1,2,3 --r2 4,5,6 --tof 60 --mu 1e5 --branch up").is_err());
+
+    let parsed = args(
+        "--r1 1,2,3 --r2 4,5,6 --mu 1e5 --departure 2016-12-31T23:59:00Z \
+         --arrival 2017-01-01T00:01:00Z",
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(parsed.tof, 121.0);
+    let dated = "--r1 1,2,3 --r2 4,5,6 --mu 1e5 --departure 2020-01-01";
+    assert!(args(dated).is_err());
+    assert!(args(&format!("{} --arrival 2020-01-02 --tof 60", dated)).is_err());
+    assert!(args(&format!("{} --arrival 2020-13-01", dated)).is_err());
+}
+
+#[test]
+fn test_json_output() {
+    let solution = LambertSolver::new(
+        Vector3D::new(5000.0, 10000.0, 2100.0),
+        Vector3D::new(-14600.0, 2500.0, 7000.0),
+        3600.0,
+        3.986004e5,
+    )
+    .unwrap()
+    .get_solution()
+    .copied()
+    .unwrap();
+    let orbit = Orbit {
+        a: f64::INFINITY,
+        inc: std::f64::consts::FRAC_PI_2,
+        nu: std::f64::consts::PI,
+        ..Orbit::default()
+    };
+    let value = solution_json(&solution, &orbit);
+    assert_eq!(value["status"], "solved");
+    assert_eq!(value["v1"][0], json!(solution.v1.x));
+    assert!(value["orbit"]["a"].is_null());
+    assert_eq!(value["orbit"]["inc_deg"], 90.0);
+    assert_eq!(value["orbit"]["nu_deg"], 180.0);
+    assert!(value["orbit"].get("inc").is_none());
+    assert!(value["warning"].is_null());
+
+    // control characters in error text are escaped
+    let error = LambertError::InvalidInput {
+        parameter: "tof\n\t\u{1}",
+        value: -1.0,
+    };
+    let text = error_json(&error).to_string();
+    assert!(!text.chars().any(char::is_control));
+    let parsed: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(parsed["error"], error.to_string());
+}
+
+#[test]
+fn test_parse_batch_args() {
+    let args = |s: &str| parse_batch_args(s.split_whitespace().map(String::from));
+    let parsed = args("cases.csv results.csv --direction retrograde")
+        .unwrap()
+        .unwrap();
+    assert_eq!(parsed.input, PathBuf::from("cases.csv"));
+    assert_eq!(parsed.output, PathBuf::from("results.csv"));
+    assert_eq!(parsed.format, BatchFormat::Csv);
+    assert_eq!(parsed.options.direction, TransferDirection::Retrograde);
+    let parsed = args("cases.txt results.txt --format jsonl")
+        .unwrap()
+        .unwrap();
+    assert_eq!(parsed.format, BatchFormat::JsonLines);
+
+    assert!(args("cases.txt results.txt").is_err());
+    assert!(args("cases.csv").is_err());
+    assert!(args("cases.csv results.csv --format xml").is_err());
+}
+
+#[test]
+fn test_parse_porkchop_args() {
+    let args = |s: &str| parse_porkchop_args(s.split_whitespace().map(String::from));
+    let parsed = args(
+        "mars.csv --from earth --to Mars --departure 2020-06-01,2020-09-29 \
+         --arrival 2021-01-01,2021-02-01 --step 10",
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(parsed.output, PathBuf::from("mars.csv"));
+    assert_eq!(parsed.from, Planet::Earth);
+    assert_eq!(parsed.to, Planet::Mars);
+    assert_eq!(parsed.departures.len(), 13);
+    assert_eq!(parsed.arrivals.len(), 4);
+
+    let valid = "mars.csv --from earth --to mars --arrival 2021-01-01,2021-02-01";
+    assert!(args(&format!("{} --departure 2020-06-01,2020-09-29", valid)).is_ok());
+    assert!(args(valid).is_err());
+    assert!(args(&format!("{} --departure 2020-06-01", valid)).is_err());
+    assert!(args(&format!(
+        "{} --departure 2020-06-01,2020-09-29 --step 0",
+        valid
+    ))
+    .is_err());
+    assert!(args(&format!(
+        "{} --departure 2020-06-01,2020-09-29 x.csv",
+        valid
+    ))
+    .is_err());
+    assert!(args("mars.csv --from vulcan").is_err());
+}
@@ -0,0 +1,215 @@
+// Physical constants of the Sun, planets, major moons and dwarf planets.
+// mu, equatorial radius and J2 from JPL, rotation from the IAU WGCCRE report,
+// sphere of influence radius a (mu / mu_primary)^(2/5) about the primary.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Body {
+    pub name: &'static str,
+    // gravitational parameter (km^3 / s^2)
+    pub mu: f64,
+    // equatorial radius (km)
+    pub radius: f64,
+    // second zonal harmonic, zero where it has not been measured
+    pub j2: f64,
+    // sidereal rotation rate (rad / s), negative for retrograde rotation
+    pub rotation_rate: f64,
+    // sphere of influence radius (km), infinite for the Sun
+    pub soi_radius: f64,
+}
+
+pub const SUN: Body = Body {
+    name: "Sun",
+    mu: 1.32712440018e11,
+    radius: 695700.0,
+    j2: 2.2e-7,
+    rotation_rate: 2.86533e-6,
+    soi_radius: f64::INFINITY,
+};
+
+pub const MERCURY: Body = Body {
+    name: "Mercury",
+    mu: 2.2031868551e4,
+    radius: 2440.53,
+    j2: 5.03e-5,
+    rotation_rate: 1.24001e-6,
+    soi_radius: 1.124e5,
+};
+
+pub const VENUS: Body = Body {
+    name: "Venus",
+    mu: 3.24858592e5,
+    radius: 6051.8,
+    j2: 4.458e-6,
+    rotation_rate: -2.99245e-7,
+    soi_radius: 6.163e5,
+};
+
+pub const EARTH: Body = Body {
+    name: "Earth",
+    mu: 3.986004418e5,
+    radius: 6378.137,
+    j2: 1.08262668e-3,
+    rotation_rate: 7.292115e-5,
+    soi_radius: 9.25e5,
+};
+
+pub const MOON: Body = Body {
+    name: "Moon",
+    mu: 4.9028000661e3,
+    radius: 1738.1,
+    j2: 2.033e-4,
+    rotation_rate: 2.6617e-6,
+    soi_radius: 6.618e4,
+};
+
+pub const MARS: Body = Body {
+    name: "Mars",
+    mu: 4.282837362e4,
+    radius: 3396.19,
+    j2: 1.96045e-3,
+    rotation_rate: 7.088218e-5,
+    soi_radius: 5.772e5,
+};
+
+pub const JUPITER: Body = Body {
+    name: "Jupiter",
+    mu: 1.26686534e8,
+    radius: 71492.0,
+    j2: 1.4736e-2,
+    rotation_rate: 1.75852e-4,
+    soi_radius: 4.822e7,
+};
+
+pub const IO: Body = Body {
+    name: "Io",
+    mu: 5959.916,
+    radius: 1821.6,
+    j2: 1.8459e-3,
+    rotation_rate: 4.11059e-5,
+    soi_radius: 7834.0,
+};
+
+pub const EUROPA: Body = Body {
+    name: "Europa",
+    mu: 3202.739,
+    radius: 1560.8,
+    j2: 4.355e-4,
+    rotation_rate: 2.04783e-5,
+    soi_radius: 9724.0,
+};
+
+pub const GANYMEDE: Body = Body {
+    name: "Ganymede",
+    mu: 9887.834,
+    radius: 2631.2,
+    j2: 1.2753e-4,
+    rotation_rate: 1.01644e-5,
+    soi_radius: 2.435e4,
+};
+
+pub const CALLISTO: Body = Body {
+    name: "Callisto",
+    mu: 7179.289,
+    radius: 2410.3,
+    j2: 3.269e-5,
+    rotation_rate: 4.35748e-6,
+    soi_radius: 3.768e4,
+};
+
+pub const SATURN: Body = Body {
+    name: "Saturn",
+    mu: 3.7931187e7,
+    radius: 60268.0,
+    j2: 1.6298e-2,
+    rotation_rate: 1.63788e-4,
+    soi_radius: 5.481e7,
+};
+
+pub const TITAN: Body = Body {
+    name: "Titan",
+    mu: 8978.1382,
+    radius: 2574.73,
+    j2: 3.3462e-5,
+    rotation_rate: 4.56081e-6,
+    soi_radius: 4.332e4,
+};
+
+pub const URANUS: Body = Body {
+    name: "Uranus",
+    mu: 5.793939e6,
+    radius: 25559.0,
+    j2: 3.34343e-3,
+    rotation_rate: -1.01237e-4,
+    soi_radius: 5.184e7,
+};
+
+pub const NEPTUNE: Body = Body {
+    name: "Neptune",
+    mu: 6.836529e6,
+    radius: 24764.0,
+    j2: 3.411e-3,
+    rotation_rate: 1.08338e-4,
+    soi_radius: 8.678e7,
+};
+
+pub const TRITON: Body = Body {
+    name: "Triton",
+    mu: 1427.598,
+    radius: 1353.4,
+    j2: 0.0,
+    rotation_rate: -1.23743e-5,
+    soi_radius: 1.196e4,
+};
+
+pub const CERES: Body = Body {
+    name: "Ceres",
+    mu: 62.6284,
+    radius: 482.1,
+    j2: 2.65e-2,
+    rotation_rate: 1.9234e-4,
+    soi_radius: 7.706e4,
+};
+
+pub const PLUTO: Body = Body {
+    name: "Pluto",
+    mu: 869.6,
+    radius: 1188.3,
+    j2: 0.0,
+    rotation_rate: -1.13855e-5,
+    soi_radius: 3.147e6,
+};
+
+pub const ERIS: Body = Body {
+    name: "Eris",
+    mu: 1108.0,
+    radius: 1163.0,
+    j2: 0.0,
+    rotation_rate: 4.60674e-6,
+    soi_radius: 5.96e6,
+};
+
+// all bodies, each planet followed by its moons
+pub static BODIES: [Body; 19] = [
+    SUN, MERCURY, VENUS, EARTH, MOON, MARS, JUPITER, IO, EUROPA, GANYMEDE, CALLISTO, SATURN, TITAN,
+    URANUS, NEPTUNE, TRITON, CERES, PLUTO, ERIS,
+];
+
+pub fn find(name: &str) -> Option<&'static Body> {
+    // case-insensitive lookup by name
+    BODIES.iter().find(|b| b.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+#[test]
+fn test_catalogue() {
+    assert_eq!(find("earth"), Some(&EARTH));
+    assert_eq!(find("Ganymede").map(|b| b.radius), Some(2631.2));
+    assert_eq!(find("Vulcan"), None);
+    for body in &BODIES {
+        assert!(body.mu > 0.0 && body.radius > 0.0 && body.j2 >= 0.0);
+        assert!(body.soi_radius > body.radius);
+        assert_eq!(find(body.name), Some(body));
+    }
+    // surface gravity of the Earth
+    assert!((EARTH.mu / EARTH.radius.powf(2.0) - 9.798e-3).abs() < 1e-5);
+}
@@ -0,0 +1,19 @@
+// Quoting of CSV fields, shared by the batch and porkchop writers
+
+pub(crate) fn csv_field(s: &str) -> String {
+    // quotes fields containing separators, quotes or line breaks
+    if s.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_csv_field() {
+    assert_eq!(csv_field("Earth"), "Earth");
+    assert_eq!(csv_field("a, b"), "\"a, b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+}
@@ -1,3 +1,8 @@
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
 use druid::{
     text::{
         format::{Formatter, Validation, ValidationError},
@@ -6,15 +11,255 @@ use druid::{
     Data, Lens,
 };
 
+use crate::bodies::{self, Body};
+use crate::ephemeris::Planet;
+use crate::lambert::LambertOptions;
+use crate::porkchop::{self, Porkchop};
+use crate::render::{self, Camera};
+use crate::scenario::{self, Scenario, ScenarioError};
+use crate::time::{Duration, Epoch};
 use crate::LambertSolver;
 use crate::Vector3D;
 
-#[derive(Clone, Data, Lens)]
+// largest porkchop grid, every node is painted as a cell
+const MAX_PORKCHOP_NODES: usize = 40000;
+// width and height of File > Export PNG images
+const PNG_SIZE: usize = 1024;
+
+// The Lambert problem and central body are saved as a Scenario, the other fields
+// are session state
+#[derive(Default, Clone, Data, Lens)]
 pub struct AppState {
     pub lambert_problem: LambertSolver,
+
+    // name of the catalogue body that sets mu and the body radius, empty for a
+    // custom body
+    pub central_body: String,
+
+    // scenario file used by File > Save
+    pub file: Option<Arc<PathBuf>>,
+
+    // most recent first
+    pub recent_files: Arc<Vec<PathBuf>>,
+
+    // result of the last file operation
+    pub file_status: String,
+
+    pub porkchop: PorkchopState,
+
+    // view direction of the 3D orbit view, also used for PNG export
+    pub camera: Camera,
+}
+
+// Inputs and result of the porkchop plot window
+#[derive(Clone, Data, Lens)]
+pub struct PorkchopState {
+    pub departure_planet: String,
+    pub arrival_planet: String,
+    // ISO-8601 dates of the first and last grid nodes
+    pub departure_start: String,
+    pub departure_end: String,
+    pub arrival_start: String,
+    pub arrival_end: String,
+    // grid spacing (days)
+    pub step: f64,
+
+    pub plot: Option<Arc<Porkchop>>,
+    // index of the node under the pointer
+    pub hover: Option<usize>,
+    // a sweep is running on a worker thread
+    pub computing: bool,
+    pub status: String,
+}
+
+// Validated grid of a porkchop sweep, run on a worker thread
+pub struct PorkchopSweep {
+    departure_planet: Planet,
+    departures: Vec<Epoch>,
+    arrival_planet: Planet,
+    arrivals: Vec<Epoch>,
+    options: LambertOptions,
+}
+
+impl PorkchopSweep {
+    pub fn run(self) -> Porkchop {
+        Porkchop::sweep(
+            self.departure_planet,
+            self.departures,
+            self.arrival_planet,
+            self.arrivals,
+            &self.options,
+        )
+    }
+}
+
+impl Default for PorkchopState {
+    fn default() -> Self {
+        // Earth to Mars in the 2020 launch window
+        Self {
+            departure_planet: "Earth".to_string(),
+            arrival_planet: "Mars".to_string(),
+            departure_start: "2020-06-01".to_string(),
+            departure_end: "2020-09-30".to_string(),
+            arrival_start: "2020-12-01".to_string(),
+            arrival_end: "2021-10-01".to_string(),
+            step: 5.0,
+            plot: None,
+            hover: None,
+            computing: false,
+            status: String::new(),
+        }
+    }
+}
+
+impl PorkchopState {
+    pub fn start_compute(&mut self, options: &LambertOptions) -> Option<PorkchopSweep> {
+        // None while a sweep is running or when the inputs are invalid
+        if self.computing {
+            return None;
+        }
+        match self.sweep(options) {
+            Ok(sweep) => {
+                let nodes = sweep.departures.len() * sweep.arrivals.len();
+                self.status = format!("computing {} transfers...", nodes);
+                self.computing = true;
+                Some(sweep)
+            }
+            Err(e) => {
+                self.status = e;
+                None
+            }
+        }
+    }
+
+    pub fn finish_compute(&mut self, plot: Arc<Porkchop>) {
+        let solved = plot.nodes.iter().filter(|n| n.outcome.is_ok()).count();
+        self.status = format!("{} of {} transfers solved", solved, plot.nodes.len());
+        self.plot = Some(plot);
+        self.hover = None;
+        self.computing = false;
+    }
+
+    fn sweep(&self, options: &LambertOptions) -> Result<PorkchopSweep, String> {
+        let planet =
+            |name: &str| Planet::find(name.trim()).ok_or(format!("unknown planet '{}'", name));
+        let epoch = |s: &str| {
+            s.trim()
+                .parse::<Epoch>()
+                .map_err(|e| format!("invalid date '{}': {}", s, e))
+        };
+        if !self.step.is_finite() || self.step <= 0.0 {
+            return Err(format!("invalid step {}, must be positive", self.step));
+        }
+        let step = Duration::from_days(self.step);
+        let departures = porkchop::epoch_range(
+            epoch(&self.departure_start)?,
+            epoch(&self.departure_end)?,
+            step,
+        );
+        let arrivals =
+            porkchop::epoch_range(epoch(&self.arrival_start)?, epoch(&self.arrival_end)?, step);
+        let nodes = departures.len() * arrivals.len();
+        if nodes > MAX_PORKCHOP_NODES {
+            return Err(format!(
+                "grid of {} nodes is too large, increase the step",
+                nodes
+            ));
+        }
+        Ok(PorkchopSweep {
+            departure_planet: planet(&self.departure_planet)?,
+            departures,
+            arrival_planet: planet(&self.arrival_planet)?,
+            arrivals,
+            options: *options,
+        })
+    }
 }
 
 impl AppState {
+    pub fn select_body(&mut self, body: &Body) {
+        self.central_body = body.name.to_string();
+        self.lambert_problem.set_central_body(body);
+    }
+
+    pub fn selected_body(&self) -> Option<&'static Body> {
+        // None for a custom body, see clear_body
+        bodies::find(&self.central_body)
+    }
+
+    pub fn clear_body(&mut self) {
+        // mu or the body radius was edited by hand
+        self.central_body.clear();
+    }
+
+    pub fn load_porkchop_node(&mut self, index: usize) {
+        // heliocentric Lambert problem between the planets at the node's epochs
+        let plot = match &self.porkchop.plot {
+            Some(plot) => plot.clone(),
+            None => return,
+        };
+        let node = &plot.nodes[index];
+        let r1 = plot.departure_planet.state(node.departure);
+        let r2 = plot.arrival_planet.state(node.arrival);
+        if let (Some((r1, v1)), Some((r2, v2))) = (r1, r2) {
+            self.select_body(&bodies::SUN);
+            self.lambert_problem.v_dep = v1;
+            self.lambert_problem.v_arr = v2;
+            self.lambert_problem
+                .set_transfer(r1, r2, node.time_of_flight().seconds());
+        }
+    }
+
+    pub fn export_png(&self, path: &Path) -> io::Result<()> {
+        // the 3D orbit view as seen by the camera
+        let image =
+            render::transfer_scene(&self.lambert_problem).render(&self.camera, PNG_SIZE, PNG_SIZE);
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        image.write_png(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn open(&mut self, path: &Path) -> Result<(), ScenarioError> {
+        // replaces the problem with a scenario file and makes it the current file
+        let scenario = Scenario::load(path)?;
+        self.lambert_problem = scenario.lambert_problem;
+        self.central_body = scenario.central_body;
+        // mu and the body radius follow the catalogue body
+        if let Some(body) = self.selected_body() {
+            self.lambert_problem.set_central_body(body);
+        }
+        self.set_file(path);
+        Ok(())
+    }
+
+    pub fn save_to(&mut self, path: &Path) -> Result<(), ScenarioError> {
+        let scenario = Scenario {
+            lambert_problem: self.lambert_problem.clone(),
+            central_body: self.central_body.clone(),
+        };
+        scenario.save(path)?;
+        self.set_file(path);
+        Ok(())
+    }
+
+    fn set_file(&mut self, path: &Path) {
+        self.file = Some(Arc::new(path.to_path_buf()));
+        self.recent_files = Arc::new(scenario::add_recent_file(&self.recent_files, path));
+    }
+
+    pub fn load_recent_files(&mut self) {
+        if let Some(path) = scenario::recent_files_path() {
+            self.recent_files = Arc::new(scenario::read_recent_files(&path));
+        }
+    }
+
+    pub fn save_recent_files(&self) -> io::Result<()> {
+        match scenario::recent_files_path() {
+            Some(path) => scenario::write_recent_files(&path, &self.recent_files),
+            None => Ok(()),
+        }
+    }
+
     pub fn test() -> Self {
         let mu = 3.986004e5;
         let r1 = Vector3D {
@@ -30,11 +275,11 @@ impl AppState {
         let ls = LambertSolver::new(r1, r2, 3600.0, mu).unwrap();
         Self {
             lambert_problem: ls,
+            ..Default::default()
         }
     }
 
     pub fn initial_earth() -> Self {
-        let mu = 3.986004e5;
         let r1 = Vector3D {
             x: 6500.0,
             y: 0.0,
@@ -45,10 +290,12 @@ impl AppState {
             y: 6500.0,
             z: 0.0,
         };
-        let ls = LambertSolver::new(r1, r2, 3688.0, mu).unwrap();
-        Self {
-            lambert_problem: ls,
-        }
+        let mut state = Self {
+            lambert_problem: LambertSolver::new(r1, r2, 3688.0, bodies::EARTH.mu).unwrap(),
+            ..Default::default()
+        };
+        state.select_body(&bodies::EARTH);
+        state
     }
 }
 
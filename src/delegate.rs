@@ -0,0 +1,134 @@
+// Handles the File menu and central body menu commands of the GUI, and porkchop
+// grids computed in the background
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use druid::{
+    commands, AppDelegate, Command, DelegateCtx, Env, FileDialogOptions, FileInfo, FileSpec,
+    Handled, Selector, Target, WindowId,
+};
+
+use crate::bodies::Body;
+use crate::data::AppState;
+use crate::porkchop::Porkchop;
+use crate::view::build_menu;
+
+pub const OPEN_RECENT: Selector<PathBuf> = Selector::new("orbital.open-recent");
+pub const SELECT_BODY: Selector<&'static Body> = Selector::new("orbital.select-body");
+// porkchop grid from the worker thread started by the Compute button
+pub const PORKCHOP_COMPUTED: Selector<Arc<Porkchop>> = Selector::new("orbital.porkchop-computed");
+// sent by the export save panel instead of SAVE_FILE_AS
+pub const EXPORT_PNG: Selector<FileInfo> = Selector::new("orbital.export-png");
+
+const SCENARIO_JSON: FileSpec = FileSpec::new("Scenario (JSON)", &["json"]);
+const SCENARIO_TOML: FileSpec = FileSpec::new("Scenario (TOML)", &["toml"]);
+const PNG_IMAGE: FileSpec = FileSpec::new("PNG image", &["png"]);
+
+pub fn open_dialog_options() -> FileDialogOptions {
+    FileDialogOptions::new()
+        .allowed_types(vec![SCENARIO_JSON, SCENARIO_TOML])
+        .default_type(SCENARIO_JSON)
+}
+
+pub fn save_dialog_options() -> FileDialogOptions {
+    open_dialog_options().default_name("scenario.json")
+}
+
+pub fn export_dialog_options() -> FileDialogOptions {
+    FileDialogOptions::new()
+        .allowed_types(vec![PNG_IMAGE])
+        .default_type(PNG_IMAGE)
+        .default_name("orbit.png")
+        .accept_command(EXPORT_PNG)
+}
+
+#[derive(Default)]
+pub struct Delegate {
+    // window whose menu lists the recent files
+    window: Option<WindowId>,
+}
+
+impl Delegate {
+    fn open(&self, ctx: &mut DelegateCtx, path: &Path, data: &mut AppState) {
+        data.file_status = match data.open(path) {
+            Ok(()) => format!("Opened {}", path.display()),
+            Err(e) => format!("Cannot open {}: {}", path.display(), e),
+        };
+        self.update_recent_files(ctx, data);
+    }
+
+    fn save(&self, ctx: &mut DelegateCtx, path: &Path, data: &mut AppState) {
+        data.file_status = match data.save_to(path) {
+            Ok(()) => format!("Saved {}", path.display()),
+            Err(e) => format!("Cannot save {}: {}", path.display(), e),
+        };
+        self.update_recent_files(ctx, data);
+    }
+
+    fn export(&self, path: &Path, data: &mut AppState) {
+        data.file_status = match data.export_png(path) {
+            Ok(()) => format!("Exported {}", path.display()),
+            Err(e) => format!("Cannot export {}: {}", path.display(), e),
+        };
+    }
+
+    fn update_recent_files(&self, ctx: &mut DelegateCtx, data: &mut AppState) {
+        if let Err(e) = data.save_recent_files() {
+            data.file_status = format!("{}, cannot store recent files: {}", data.file_status, e);
+        }
+        if let Some(window) = self.window {
+            ctx.set_menu(build_menu(data), window);
+        }
+    }
+}
+
+impl AppDelegate<AppState> for Delegate {
+    fn command(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        target: Target,
+        cmd: &Command,
+        data: &mut AppState,
+        _env: &Env,
+    ) -> Handled {
+        if let Some(info) = cmd.get(commands::OPEN_FILE) {
+            self.open(ctx, info.path(), data);
+        } else if let Some(path) = cmd.get(OPEN_RECENT) {
+            self.open(ctx, path, data);
+        } else if let Some(body) = cmd.get(SELECT_BODY) {
+            data.select_body(body);
+        } else if let Some(plot) = cmd.get(PORKCHOP_COMPUTED) {
+            data.porkchop.finish_compute(plot.clone());
+        } else if let Some(info) = cmd.get(commands::SAVE_FILE_AS) {
+            self.save(ctx, info.path(), data);
+        } else if let Some(info) = cmd.get(EXPORT_PNG) {
+            self.export(info.path(), data);
+        } else if cmd.is(commands::SAVE_FILE) {
+            // Save without a current file behaves as Save As
+            match data.file.clone() {
+                Some(path) => self.save(ctx, &path, data),
+                None => ctx.submit_command(
+                    commands::SHOW_SAVE_PANEL
+                        .with(save_dialog_options())
+                        .to(target),
+                ),
+            }
+        } else {
+            return Handled::No;
+        }
+        Handled::Yes
+    }
+
+    fn window_added(
+        &mut self,
+        id: WindowId,
+        _data: &mut AppState,
+        _env: &Env,
+        _ctx: &mut DelegateCtx,
+    ) {
+        // the main window is added first, the plot windows have no menu
+        if self.window.is_none() {
+            self.window = Some(id);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+// Low-precision planetary ephemeris from JPL's approximate Keplerian elements
+// (Standish, "Keplerian Elements for Approximate Positions of the Major Planets",
+// table 1), valid from 1800 to 2050. Positions and velocities are heliocentric
+// in the J2000 ecliptic frame.
+use std::f64::consts::PI;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::bodies::{self, Body};
+use crate::kepler;
+use crate::lambert::{LambertError, LambertOptions};
+use crate::time::{Epoch, TimeScale, J2000};
+use crate::{LambertSolver, Orbit, Vector3D};
+
+// astronomical unit (km)
+const AU: f64 = 1.495978707e8;
+// validity of the elements: 1800-01-01 up to, not including, 2051-01-01 so that
+// all of 2050 is covered (TDB Julian dates)
+pub const VALID_FROM: f64 = 2378496.5;
+pub const VALID_TO: f64 = 2470172.5;
+
+// Earth is the Earth-Moon barycenter, which is within 5000 km of the Earth
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Earth,
+    Mars,
+    Jupiter,
+    Saturn,
+    Uranus,
+    Neptune,
+    Pluto,
+}
+
+// a (AU), e, I, L, longitude of perihelion, longitude of the ascending node (deg)
+// at J2000 and their rates per Julian century
+type Elements = [[f64; 6]; 2];
+
+impl Planet {
+    pub const ALL: [Planet; 9] = [
+        Self::Mercury,
+        Self::Venus,
+        Self::Earth,
+        Self::Mars,
+        Self::Jupiter,
+        Self::Saturn,
+        Self::Uranus,
+        Self::Neptune,
+        Self::Pluto,
+    ];
+
+    pub fn find(name: &str) -> Option<Self> {
+        // case-insensitive lookup by name
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|p| p.body().name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn body(&self) -> &'static Body {
+        match self {
+            Self::Mercury => &bodies::MERCURY,
+            Self::Venus => &bodies::VENUS,
+            Self::Earth => &bodies::EARTH,
+            Self::Mars => &bodies::MARS,
+            Self::Jupiter => &bodies::JUPITER,
+            Self::Saturn => &bodies::SATURN,
+            Self::Uranus => &bodies::URANUS,
+            Self::Neptune => &bodies::NEPTUNE,
+            Self::Pluto => &bodies::PLUTO,
+        }
+    }
+
+    #[rustfmt::skip]
+    fn elements(&self) -> Elements {
+        match self {
+            Self::Mercury => [
+                [0.38709927, 0.20563593, 7.00497902, 252.25032350, 77.45779628, 48.33076593],
+                [0.00000037, 0.00001906, -0.00594749, 149472.67411175, 0.16047689, -0.12534081],
+            ],
+            Self::Venus => [
+                [0.72333566, 0.00677672, 3.39467605, 181.97909950, 131.60246718, 76.67984255],
+                [0.00000390, -0.00004107, -0.00078890, 58517.81538729, 0.00268329, -0.27769418],
+            ],
+            Self::Earth => [
+                [1.00000261, 0.01671123, -0.00001531, 100.46457166, 102.93768193, 0.0],
+                [0.00000562, -0.00004392, -0.01294668, 35999.37244981, 0.32327364, 0.0],
+            ],
+            Self::Mars => [
+                [1.52371034, 0.09339410, 1.84969142, -4.55343205, -23.94362959, 49.55953891],
+                [0.00001847, 0.00007882, -0.00813131, 19140.30268499, 0.44441088, -0.29257343],
+            ],
+            Self::Jupiter => [
+                [5.20288700, 0.04838624, 1.30439695, 34.39644051, 14.72847983, 100.47390909],
+                [-0.00011607, -0.00013253, -0.00183714, 3034.74612775, 0.21252668, 0.20469106],
+            ],
+            Self::Saturn => [
+                [9.53667594, 0.05386179, 2.48599187, 49.95424423, 92.59887831, 113.66242448],
+                [-0.00125060, -0.00050991, 0.00193609, 1222.49362201, -0.41897216, -0.28867794],
+            ],
+            Self::Uranus => [
+                [19.18916464, 0.04725744, 0.77263783, 313.23810451, 170.95427630, 74.01692503],
+                [-0.00196176, -0.00004397, -0.00242939, 428.48202785, 0.40805281, 0.04240589],
+            ],
+            Self::Neptune => [
+                [30.06992276, 0.00859048, 1.77004347, -55.12002969, 44.96476227, 131.78422574],
+                [0.00026291, 0.00005105, 0.00035372, 218.45945325, -0.32241464, -0.00508664],
+            ],
+            Self::Pluto => [
+                [39.48211675, 0.24882730, 17.14001206, 238.92903833, 224.06891629, 110.30393684],
+                [-0.00031596, 0.00005170, 0.00004818, 145.20780515, -0.04062942, -0.01183482],
+            ],
+        }
+    }
+
+    pub fn orbit(&self, epoch: Epoch) -> Option<Orbit> {
+        // osculating heliocentric orbit at the epoch, None outside 1800-2050
+        let jd = epoch.jd(TimeScale::Tdb);
+        if !(VALID_FROM..VALID_TO).contains(&jd) {
+            return None;
+        }
+        let t = (jd - J2000) / 36525.0;
+        let [elements, rates] = self.elements();
+        let element = |i: usize| elements[i] + rates[i] * t;
+
+        let a = element(0) * AU;
+        let ecc = element(1);
+        let inc = element(2).to_radians();
+        let mean_longitude = element(3).to_radians();
+        let longitude_of_perihelion = element(4).to_radians();
+        let lan = element(5).to_radians();
+
+        let m = (mean_longitude - longitude_of_perihelion).rem_euclid(2.0 * PI);
+        Some(Orbit::new(
+            a,
+            ecc,
+            inc,
+            lan.rem_euclid(2.0 * PI),
+            (longitude_of_perihelion - lan).rem_euclid(2.0 * PI),
+            kepler::mean_to_true(m, ecc).rem_euclid(2.0 * PI),
+            bodies::SUN.mu,
+        ))
+    }
+
+    pub fn state(&self, epoch: Epoch) -> Option<(Vector3D, Vector3D)> {
+        // heliocentric position (km) and velocity (km/s) at the epoch
+        self.orbit(epoch).map(|orbit| orbit.to_rv(bodies::SUN.mu))
+    }
+}
+
+pub fn lambert_solver(
+    departure: Planet,
+    departure_epoch: Epoch,
+    arrival: Planet,
+    arrival_epoch: Epoch,
+    options: LambertOptions,
+) -> Result<LambertSolver, LambertError> {
+    // heliocentric Lambert problem from the departure planet to the arrival planet,
+    // the time of flight is the time between the epochs
+    let (r1, _) = departure
+        .state(departure_epoch)
+        .ok_or(LambertError::InvalidInput {
+            parameter: "departure date",
+            value: departure_epoch.jd(TimeScale::Tdb),
+        })?;
+    let (r2, _) = arrival
+        .state(arrival_epoch)
+        .ok_or(LambertError::InvalidInput {
+            parameter: "arrival date",
+            value: arrival_epoch.jd(TimeScale::Tdb),
+        })?;
+    let tof = (arrival_epoch - departure_epoch).seconds();
+    LambertSolver::with_options(r1, r2, tof, bodies::SUN.mu, options)
+}
+
+#[cfg(test)]
+#[test]
+fn test_planet_states() {
+    // J2000 positions from the DE ephemeris (AU), the elements are good to about 0.01 AU
+    let cases = [
+        (Planet::Earth, Vector3D::new(-0.1771, 0.9672, 0.0)),
+        (Planet::Mars, Vector3D::new(1.3907, -0.0134, -0.0344)),
+        (Planet::Jupiter, Vector3D::new(4.0012, 2.9385, -0.1017)),
+    ];
+    let j2000 = Epoch::from_jd(J2000, TimeScale::Tdb);
+    for &(planet, r_ans) in &cases {
+        let (r, v) = planet.state(j2000).unwrap();
+        assert!((r / AU - r_ans).mag() < 0.01);
+        // vis-viva
+        let a = planet.orbit(j2000).unwrap().a;
+        assert!((v * v - bodies::SUN.mu * (2.0 / r.mag() - 1.0 / a)).abs() < 1e-6);
+    }
+
+    let epoch = |s: &str| s.parse::<Epoch>().unwrap();
+    assert!(Planet::Mars.state(epoch("1800-01-01T00:00 TDB")).is_some());
+    assert!(Planet::Mars.state(epoch("2050-12-31T00:00 TDB")).is_some());
+    assert!(Planet::Mars.state(epoch("2050-12-31T23:59:59 TDB")).is_some());
+    assert_eq!(Planet::Mars.state(epoch("1799-12-31T23:59:59 TDB")), None);
+    assert_eq!(Planet::Mars.state(epoch("2051-01-01T00:00 TDB")), None);
+    assert_eq!(epoch("2051-01-01T00:00 TDB").jd(TimeScale::Tdb), VALID_TO);
+
+    assert_eq!(Planet::find("mars"), Some(Planet::Mars));
+    assert_eq!(Planet::find("Moon"), None);
+}
+
+#[test]
+fn test_earth_mars_transfer() {
+    // Mars 2020: launched 2020-07-30, landed 2021-02-18 with C3 = 14.5 km^2/s^2
+    let departure: Epoch = "2020-07-30T11:50:00Z".parse().unwrap();
+    let arrival: Epoch = "2021-02-18T20:55:00Z".parse().unwrap();
+    let ls = lambert_solver(
+        Planet::Earth,
+        departure,
+        Planet::Mars,
+        arrival,
+        LambertOptions::default(),
+    )
+    .unwrap();
+    let (_, v_earth) = Planet::Earth.state(departure).unwrap();
+    let c3 = (ls.get_v1() - v_earth).mag().powf(2.0);
+    assert!((c3 - 14.5).abs() < 2.0);
+
+    assert!(matches!(
+        lambert_solver(
+            Planet::Earth,
+            "1700-01-01".parse().unwrap(),
+            Planet::Mars,
+            arrival,
+            LambertOptions::default()
+        ),
+        Err(LambertError::InvalidInput {
+            parameter: "departure date",
+            ..
+        })
+    ));
+}
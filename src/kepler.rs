@@ -0,0 +1,155 @@
+// Anomaly conversions and Kepler equation solvers for all conic sections
+use std::f64::consts::PI;
+
+// orbits with |ecc - 1| below this value are treated as parabolic
+pub const PARABOLIC_TOLERANCE: f64 = 1e-9;
+
+const TOLERANCE: f64 = 1e-14;
+const MAX_ITERATIONS: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conic {
+    Elliptic,
+    Parabolic,
+    Hyperbolic,
+}
+
+impl Conic {
+    pub fn from_ecc(ecc: f64) -> Self {
+        if (ecc - 1.0).abs() < PARABOLIC_TOLERANCE {
+            Self::Parabolic
+        } else if ecc < 1.0 {
+            Self::Elliptic
+        } else {
+            Self::Hyperbolic
+        }
+    }
+}
+
+pub fn true_to_eccentric(nu: f64, ecc: f64) -> f64 {
+    2.0 * (((1.0 - ecc) / (1.0 + ecc)).sqrt() * (nu / 2.0).tan()).atan()
+}
+
+pub fn eccentric_to_true(e: f64, ecc: f64) -> f64 {
+    2.0 * (((1.0 + ecc) / (1.0 - ecc)).sqrt() * (e / 2.0).tan()).atan()
+}
+
+pub fn eccentric_to_mean(e: f64, ecc: f64) -> f64 {
+    e - ecc * e.sin()
+}
+
+pub fn mean_to_eccentric(m: f64, ecc: f64) -> f64 {
+    // Newton iterations on Kepler equation M = E - e sin(E)
+    let m = (m + PI).rem_euclid(2.0 * PI) - PI;
+    let mut e = if ecc < 0.8 {
+        m + ecc * m.sin()
+    } else {
+        PI * m.signum()
+    };
+    for _ in 0..MAX_ITERATIONS {
+        let delta = (e - ecc * e.sin() - m) / (1.0 - ecc * e.cos());
+        e -= delta;
+        if delta.abs() < TOLERANCE {
+            break;
+        }
+    }
+    e
+}
+
+pub fn true_to_hyperbolic(nu: f64, ecc: f64) -> f64 {
+    2.0 * (((ecc - 1.0) / (ecc + 1.0)).sqrt() * (nu / 2.0).tan()).atanh()
+}
+
+pub fn hyperbolic_to_true(h: f64, ecc: f64) -> f64 {
+    2.0 * (((ecc + 1.0) / (ecc - 1.0)).sqrt() * (h / 2.0).tanh()).atan()
+}
+
+pub fn hyperbolic_to_mean(h: f64, ecc: f64) -> f64 {
+    ecc * h.sinh() - h
+}
+
+pub fn mean_to_hyperbolic(m: f64, ecc: f64) -> f64 {
+    // Newton iterations on hyperbolic Kepler equation M = e sinh(H) - H
+    let mut h = (m / ecc).asinh();
+    for _ in 0..MAX_ITERATIONS {
+        let delta = (ecc * h.sinh() - h - m) / (ecc * h.cosh() - 1.0);
+        h -= delta;
+        if delta.abs() < TOLERANCE * (1.0 + h.abs()) {
+            break;
+        }
+    }
+    h
+}
+
+pub fn true_to_parabolic(nu: f64) -> f64 {
+    (nu / 2.0).tan()
+}
+
+pub fn parabolic_to_true(d: f64) -> f64 {
+    2.0 * d.atan()
+}
+
+pub fn parabolic_to_mean(d: f64) -> f64 {
+    // Barker's equation
+    d + d.powf(3.0) / 3.0
+}
+
+pub fn mean_to_parabolic(m: f64) -> f64 {
+    // closed form solution of Barker's equation
+    let b = 1.5 * m;
+    let a = (b.abs() + (1.0 + b.powf(2.0)).sqrt()).powf(2.0 / 3.0);
+    b.signum() * 2.0 * a * b.abs() / (1.0 + a + a.powf(2.0))
+}
+
+// Mean anomaly is M = n (t - T), with mean motion n = sqrt(mu / |a|^3)
+// for elliptic and hyperbolic orbits and n = 2 sqrt(mu / p^3) for parabolic ones.
+pub fn true_to_mean(nu: f64, ecc: f64) -> f64 {
+    match Conic::from_ecc(ecc) {
+        Conic::Elliptic => eccentric_to_mean(true_to_eccentric(nu, ecc), ecc),
+        Conic::Parabolic => parabolic_to_mean(true_to_parabolic(nu)),
+        Conic::Hyperbolic => hyperbolic_to_mean(true_to_hyperbolic(nu, ecc), ecc),
+    }
+}
+
+pub fn mean_to_true(m: f64, ecc: f64) -> f64 {
+    match Conic::from_ecc(ecc) {
+        Conic::Elliptic => eccentric_to_true(mean_to_eccentric(m, ecc), ecc),
+        Conic::Parabolic => parabolic_to_true(mean_to_parabolic(m)),
+        Conic::Hyperbolic => hyperbolic_to_true(mean_to_hyperbolic(m, ecc), ecc),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_elliptic_anomalies() {
+    for &ecc in &[0.0, 0.1, 0.5, 0.9, 0.999] {
+        for i in 0..12 {
+            let nu = -PI + 0.5 * f64::from(i) + 0.1;
+            let m = true_to_mean(nu, ecc);
+            assert!((mean_to_true(m, ecc) - nu).abs() < 1e-9);
+        }
+    }
+    assert!((mean_to_eccentric(eccentric_to_mean(2.0, 0.7), 0.7) - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_hyperbolic_anomalies() {
+    for &ecc in &[1.01f64, 1.5, 3.0, 20.0] {
+        let nu_max = (-1.0 / ecc).acos();
+        for i in 1..10 {
+            let nu = nu_max * (f64::from(i) / 5.0 - 1.0);
+            let m = true_to_mean(nu, ecc);
+            assert!((mean_to_true(m, ecc) - nu).abs() < 1e-9);
+        }
+    }
+    assert!((mean_to_hyperbolic(hyperbolic_to_mean(5.0, 1.2), 1.2) - 5.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_parabolic_anomalies() {
+    for i in 0..11 {
+        let nu = 0.3 * (f64::from(i) - 5.0);
+        let m = true_to_mean(nu, 1.0);
+        assert!((mean_to_true(m, 1.0) - nu).abs() < 1e-12);
+    }
+}
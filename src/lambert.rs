@@ -1,54 +1,434 @@
+use std::error::Error;
 use std::f64::consts::PI;
+use std::fmt;
 
+#[cfg(feature = "gui")]
 use druid::{Data, Lens};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
+use crate::bodies::Body;
 use crate::Orbit;
 use crate::Vector3D;
 
-#[derive(Debug, Clone, Data, PartialEq)]
-pub enum LambertSolverStatus {
-    Initialized,
-    Converged,
-    NotConverged,
-    MultiRevolution,
-    CollinearVectors,
-    InvalidInput,
-    InternalError,
+mod gooding;
+mod universal;
+
+pub use gooding::Gooding;
+pub use universal::UniversalVariables;
+
+// default iteration settings, iterations stop when the step of the iteration
+// variable is below tolerance
+const TOLERANCE: f64 = 1e-08;
+const MAX_ITERATIONS: u32 = 10;
+
+// distance from x = 1 where Battin series is used for time-of-flight
+const BATTIN_RANGE: f64 = 0.01;
+
+// default distance of the transfer angle from 0 or 180 degrees (radians)
+// below which the solution is flagged as ill-conditioned
+const COLLINEARITY_THRESHOLD: f64 = 0.01;
+
+// Multi-revolution problems have two solutions for every number of revolutions,
+// named after the side of the time-of-flight minimum they lie on
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LambertBranch {
+    #[default]
+    Left,
+    Right,
 }
 
-impl Default for LambertSolverStatus {
+// Direction of motion along the transfer orbit. Prograde and retrograde are
+// measured about +Z; short-way and long-way pick the transfer angle explicitly
+// and are the only reliable choice when the transfer plane contains the Z axis.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TransferDirection {
+    #[default]
+    Prograde,
+    Retrograde,
+    ShortWay,
+    LongWay,
+}
+
+// Starting point of the zero revolution Householder iterations,
+// multi-revolution branches always use Izzo's branch guesses
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InitialGuess {
+    // piecewise guess from Izzo's paper
+    #[default]
+    Izzo,
+    // rational approximation used in pykep, smoother across T(x = 0)
+    Rational,
+    // user supplied x
+    Fixed(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LambertOptions {
+    pub direction: TransferDirection,
+    // orbit normal (along angular momentum) of the transfer, overrides direction.
+    // Required for exactly 180 degree transfers, where r1 and r2 do not define a plane.
+    pub normal: Option<Vector3D>,
+    pub collinearity_threshold: f64,
+    pub tolerance: f64,
+    pub max_iterations: u32,
+    pub initial_guess: InitialGuess,
+    // full revolutions of the transfer, and which solution of the pair to take
+    // for one or more revolutions; solved with Izzo's multi-revolution branch
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub revolutions: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub branch: LambertBranch,
+}
+
+impl Default for LambertOptions {
     fn default() -> Self {
-        Self::Initialized
+        Self {
+            direction: TransferDirection::default(),
+            normal: None,
+            collinearity_threshold: COLLINEARITY_THRESHOLD,
+            tolerance: TOLERANCE,
+            max_iterations: MAX_ITERATIONS,
+            initial_guess: InitialGuess::default(),
+            revolutions: 0,
+            branch: LambertBranch::default(),
+        }
     }
 }
 
-#[derive(Default, Data, Clone, Lens)]
-pub struct LambertSolver {
-    // first radius-vector
-    r1_v: Vector3D,
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LambertWarning {
+    // transfer angle (radians) is within collinearity_threshold of 0 or 180 degrees
+    // and no normal was given, so the transfer plane is poorly defined
+    NearCollinear { transfer_angle: f64 },
+}
+
+impl fmt::Display for LambertWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NearCollinear { transfer_angle } => write!(
+                f,
+                "transfer angle {:.4} deg is close to collinear, transfer plane is ill-conditioned",
+                transfer_angle.to_degrees()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum LambertError {
+    // non-positive or non-finite input parameter
+    InvalidInput {
+        parameter: &'static str,
+        value: f64,
+    },
+    // radius-vectors do not define a transfer plane
+    CollinearVectors {
+        r1: Vector3D,
+        r2: Vector3D,
+    },
+    // last_step is the last step of the iteration variable as in LambertSolution,
+    // residual the time-of-flight error in seconds
+    NotConverged {
+        iterations: u32,
+        last_step: f64,
+        residual: f64,
+    },
+    // time-of-flight is below the minimum (seconds) of the requested revolutions
+    TooManyRevolutions {
+        revolutions: u32,
+        min_tof: f64,
+    },
+}
+
+impl fmt::Display for LambertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidInput { parameter, value } => {
+                write!(f, "invalid input, {} = {}", parameter, value)
+            }
+            Self::CollinearVectors { r1, r2 } => write!(
+                f,
+                "cannot determine orbit, collinear radius-vectors r1 = ({}) and r2 = ({})",
+                r1, r2
+            ),
+            Self::NotConverged {
+                iterations,
+                last_step,
+                residual,
+            } => write!(
+                f,
+                "solution did not converge after {} iterations, last step {:.3e}, \
+                 time-of-flight residual {:.3e} s",
+                iterations, last_step, residual
+            ),
+            Self::TooManyRevolutions {
+                revolutions,
+                min_tof,
+            } => write!(
+                f,
+                "time-of-flight is too short for {} revolutions, minimum {:.3} s",
+                revolutions, min_tof
+            ),
+        }
+    }
+}
+
+impl Error for LambertError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LambertSolution {
+    pub v1: Vector3D,
+    pub v2: Vector3D,
+    // number of full revolutions, branch is None for zero revolutions
+    pub revolutions: u32,
+    pub branch: Option<LambertBranch>,
+    // convergence diagnostics: iterations used, magnitude of the last step and
+    // time-of-flight residual in seconds. The step is in the iteration variable of
+    // the algorithm: x for Izzo (Householder) and Gooding (Halley), the universal
+    // variable z (rad^2) for UniversalVariables. Only the residual compares across
+    // algorithms.
+    pub iterations: u32,
+    pub last_step: f64,
+    pub residual: f64,
+    pub warning: Option<LambertWarning>,
+}
+
+pub fn solve(
+    r1: Vector3D,
+    r2: Vector3D,
+    tof: f64,
+    mu: f64,
+    options: &LambertOptions,
+) -> Result<LambertSolution, LambertError> {
+    let geometry = Geometry::new(r1, r2, tof, mu, options)?;
+    if options.revolutions > 0 {
+        return geometry.multi_revolution_solution(options.revolutions, options.branch);
+    }
+
+    // T if x = 0
+    let t_0 = geometry.lambda.acos() + geometry.lambda * (1.0 - geometry.lambda.powf(2.0)).sqrt();
+    // T if x = 1
+    let t_1 = 2.0 / 3.0 * (1.0 - geometry.lambda.powf(3.0));
+
+    // guess x based on non dimentional time-of-flight
+    let t_nd = geometry.t_nd;
+    let x0 = match options.initial_guess {
+        InitialGuess::Izzo => {
+            if t_nd >= t_0 {
+                (t_0 / t_nd).powf(2.0 / 3.0) - 1.0
+            } else if t_nd < t_1 {
+                2.5 * t_1 * (t_1 - t_nd) / t_nd / (1.0 - geometry.lambda.powf(5.0)) + 1.0
+            } else {
+                (t_0 / t_nd).powf((t_1 / t_0).log2()) - 1.0
+            }
+        }
+        InitialGuess::Rational => {
+            if t_nd >= t_0 {
+                -(t_nd - t_0) / (t_nd - t_0 + 4.0)
+            } else if t_nd <= t_1 {
+                t_1 * (t_1 - t_nd) / (0.4 * (1.0 - geometry.lambda.powf(5.0)) * t_nd) + 1.0
+            } else {
+                (t_nd / t_0).powf(2f64.ln() / (t_1 / t_0).ln()) - 1.0
+            }
+        }
+        InitialGuess::Fixed(x) => x,
+    };
+
+    geometry.solution(x0, 0, None)
+}
+
+pub fn solve_multi_revolution(
+    r1: Vector3D,
+    r2: Vector3D,
+    tof: f64,
+    mu: f64,
+    max_revolutions: u32,
+    options: &LambertOptions,
+) -> Result<Vec<Result<LambertSolution, LambertError>>, LambertError> {
+    // left and right branch solutions for 1..=max_revolutions full revolutions,
+    // stops at the first revolution count the time-of-flight is too short for
+    let geometry = Geometry::new(r1, r2, tof, mu, options)?;
+    let mut solutions = Vec::new();
+    for revolutions in 1..=max_revolutions {
+        if geometry.min_time_of_flight(revolutions) > geometry.t_nd {
+            break;
+        }
+        for &branch in &[LambertBranch::Left, LambertBranch::Right] {
+            solutions.push(geometry.multi_revolution_solution(revolutions, branch));
+        }
+    }
+    Ok(solutions)
+}
+
+// Solver of the Lambert problem, lets trajectories be cross-checked with independent
+// formulations. Only Izzo solves multi-revolution transfers.
+pub trait LambertAlgorithm {
+    fn solve(
+        &self,
+        r1: Vector3D,
+        r2: Vector3D,
+        tof: f64,
+        mu: f64,
+        options: &LambertOptions,
+    ) -> Result<LambertSolution, LambertError>;
+}
+
+// Izzo's formulation, the default solver of the crate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Izzo;
+
+impl LambertAlgorithm for Izzo {
+    fn solve(
+        &self,
+        r1: Vector3D,
+        r2: Vector3D,
+        tof: f64,
+        mu: f64,
+        options: &LambertOptions,
+    ) -> Result<LambertSolution, LambertError> {
+        solve(r1, r2, tof, mu, options)
+    }
+}
+
+fn check_inputs(
+    r1: Vector3D,
+    r2: Vector3D,
+    tof: f64,
+    mu: f64,
+    options: &LambertOptions,
+) -> Result<(), LambertError> {
+    let inputs = [
+        ("mu", mu),
+        ("time-of-flight", tof),
+        ("|r1|", r1.mag()),
+        ("|r2|", r2.mag()),
+        ("tolerance", options.tolerance),
+    ];
+    for &(parameter, value) in &inputs {
+        if !(value > 0.0 && value.is_finite()) {
+            return Err(LambertError::InvalidInput { parameter, value });
+        }
+    }
+    if options.collinearity_threshold.is_nan() || options.collinearity_threshold < 0.0 {
+        return Err(LambertError::InvalidInput {
+            parameter: "collinearity threshold",
+            value: options.collinearity_threshold,
+        });
+    }
+    Ok(())
+}
+
+fn check_single_revolution(options: &LambertOptions) -> Result<(), LambertError> {
+    // for the solvers without a multi-revolution branch
+    if options.revolutions > 0 {
+        return Err(LambertError::InvalidInput {
+            parameter: "revolutions",
+            value: f64::from(options.revolutions),
+        });
+    }
+    Ok(())
+}
+
+struct TransferPlane {
+    // unit normal along the angular momentum of the transfer
+    i_h: Vector3D,
+    // transfer angle is above 180 degrees
+    long_way: bool,
+    warning: Option<LambertWarning>,
+}
+
+fn transfer_plane(
+    r1: Vector3D,
+    r2: Vector3D,
+    options: &LambertOptions,
+) -> Result<TransferPlane, LambertError> {
+    let h = r1.cross(r2);
+    let transfer_angle = h.mag().atan2(r1 * r2);
+    let near_collinear = transfer_angle < options.collinearity_threshold
+        || PI - transfer_angle < options.collinearity_threshold;
+
+    if let Some(normal) = options.normal {
+        // plane of r1 and the given normal, r1 x r2 only picks the transfer angle
+        let i_h = (normal - (normal * r1) / (r1 * r1) * r1).norm();
+        if !i_h.mag().is_normal() {
+            return Err(LambertError::InvalidInput {
+                parameter: "|normal x r1|",
+                value: normal.cross(r1).mag(),
+            });
+        }
+        if h.mag() == 0.0 && r1 * r2 > 0.0 {
+            // same direction, the transfer degenerates to a rectilinear orbit
+            return Err(LambertError::CollinearVectors { r1, r2 });
+        }
+        // exact 180 degree transfers are the same either way
+        let long_way = h * i_h < 0.0;
+        let i_h = if near_collinear {
+            i_h
+        } else if long_way {
+            -h.norm()
+        } else {
+            h.norm()
+        };
+        return Ok(TransferPlane {
+            i_h,
+            long_way,
+            warning: None,
+        });
+    }
+
+    if h.mag() == 0.0 {
+        return Err(LambertError::CollinearVectors { r1, r2 });
+    }
+    let i_h = h.norm();
+    let long_way = match options.direction {
+        TransferDirection::Prograde => i_h.z < 0.0,
+        TransferDirection::Retrograde => i_h.z > 0.0,
+        TransferDirection::ShortWay => false,
+        TransferDirection::LongWay => true,
+    };
+    Ok(TransferPlane {
+        i_h: if long_way { -i_h } else { i_h },
+        long_way,
+        warning: if near_collinear {
+            Some(LambertWarning::NearCollinear { transfer_angle })
+        } else {
+            None
+        },
+    })
+}
+
+// Non dimensional problem geometry after Izzo
+struct Geometry {
     i_r1: Vector3D,
     r1: f64,
 
-    // second radius-vector
-    r2_v: Vector3D,
     i_r2: Vector3D,
     r2: f64,
 
-    // time of flight
-    t: f64,
-
     // gravitational body constant
     mu: f64,
 
     // chorde
-    c_v: Vector3D,
     c: f64,
 
     // half of perimeter
     s: f64,
 
     lambda: f64,
-    i_h: Vector3D,
 
     i_t1: Vector3D,
     i_t2: Vector3D,
@@ -56,175 +436,398 @@ pub struct LambertSolver {
     // non dimensional time-of-flight
     t_nd: f64,
 
-    // fitting parameter
-    x: f64,
-    y: f64,
-
-    // Output parameters
-    pub status: LambertSolverStatus,
-    v1: Vector3D,
-    v2: Vector3D,
+    warning: Option<LambertWarning>,
+    tolerance: f64,
+    max_iterations: u32,
 }
 
-impl LambertSolver {
-    pub fn new(r1: Vector3D, r2: Vector3D, t: f64, mu: f64) -> Result<Self, LambertSolverStatus> {
-        let mut new = Self {
-            r1_v: r1,
-            r2_v: r2,
-            t: t,
-            mu: mu,
-            ..Default::default()
-        };
-        match new.calculate_params() {
-            Ok(_) => Ok(new),
-            Err(e) => {
-                new.status = e.clone();
-                Err(e)
-            }
-        }
-    }
-
-    fn calculate_params(&mut self) -> Result<(), LambertSolverStatus> {
-        if self.mu <= 0.0 || self.t <= 0.0 {
-            return Err(LambertSolverStatus::InvalidInput);
-        }
-        if self.r1_v.cross(self.r2_v).mag() == 0.0 {
-            return Err(LambertSolverStatus::CollinearVectors);
-        }
-        self.r1 = self.r1_v.mag();
-        self.i_r1 = self.r1_v.norm();
-        self.r2 = self.r2_v.mag();
-        self.i_r2 = self.r2_v.norm();
-
-        self.c_v = self.r2_v - self.r1_v;
-        self.c = self.c_v.mag();
-
-        self.s = 0.5 * (self.r1 + self.r2 + self.c);
+impl Geometry {
+    fn new(
+        r1_v: Vector3D,
+        r2_v: Vector3D,
+        t: f64,
+        mu: f64,
+        options: &LambertOptions,
+    ) -> Result<Self, LambertError> {
+        check_inputs(r1_v, r2_v, t, mu, options)?;
+        let r1 = r1_v.mag();
+        let i_r1 = r1_v.norm();
+        let r2 = r2_v.mag();
+        let i_r2 = r2_v.norm();
 
-        self.lambda = (1.0 - self.c / self.s).sqrt();
-        self.i_h = self.i_r1.cross(self.i_r2).norm();
-        if self.i_h.z < 0.0 {
-            self.lambda = -self.lambda;
-            self.i_h = -self.i_h;
-        }
-        self.i_t1 = self.i_h.cross(self.i_r1).norm();
-        self.i_t2 = self.i_h.cross(self.i_r2).norm();
+        let c = (r2_v - r1_v).mag();
 
-        self.t_nd = (2.0 * self.mu / self.s.powf(3.0)).sqrt() * self.t;
+        let s = 0.5 * (r1 + r2 + c);
 
-        self.find_x()?;
-        self.calculate_velocity();
+        let plane = transfer_plane(r1_v, r2_v, options)?;
+        let i_h = plane.i_h;
+        let lambda = if plane.long_way {
+            -(1.0 - c / s).sqrt()
+        } else {
+            (1.0 - c / s).sqrt()
+        };
 
-        Ok(())
+        Ok(Self {
+            i_r1,
+            r1,
+            i_r2,
+            r2,
+            mu,
+            c,
+            s,
+            lambda,
+            i_t1: i_h.cross(i_r1).norm(),
+            i_t2: i_h.cross(i_r2).norm(),
+            t_nd: (2.0 * mu / s.powf(3.0)).sqrt() * t,
+            warning: plane.warning,
+            tolerance: options.tolerance,
+            max_iterations: options.max_iterations,
+        })
     }
 
-    fn set_y(&mut self) {
-        self.y = (1.0 - self.lambda.powf(2.0) * (1.0 - self.x.powf(2.0))).sqrt();
+    fn y_of(&self, x: f64) -> f64 {
+        (1.0 - self.lambda.powf(2.0) * (1.0 - x.powf(2.0))).sqrt()
     }
 
-    fn psi(&self) -> f64 {
-        if self.x < 1.0 {
-            (self.x * self.y + self.lambda * (1.0 - self.x.powf(2.0))).acos()
-        } else if self.x > 1.0 {
-            (self.x * self.y - self.lambda * (self.x.powf(2.0) - 1.0)).acosh()
+    fn psi(&self, x: f64, y: f64) -> f64 {
+        if x < 1.0 {
+            (x * y + self.lambda * (1.0 - x.powf(2.0))).acos()
+        } else if x > 1.0 {
+            (x * y - self.lambda * (x.powf(2.0) - 1.0)).acosh()
         } else {
             0.0
         }
     }
 
-    fn find_x(&mut self) -> Result<(), LambertSolverStatus> {
-        if self.lambda.abs() >= 1.0 {
-            return Err(LambertSolverStatus::InternalError);
-        }
-        if self.t_nd / PI >= 1.0 {
-            return Err(LambertSolverStatus::MultiRevolution);
+    fn time_of_flight(&self, x: f64, revolutions: u32) -> f64 {
+        // non dimensional time-of-flight for given x and number of full revolutions
+        let y = self.y_of(x);
+        let m_pi = f64::from(revolutions) * PI;
+        if (x - 1.0).abs() < BATTIN_RANGE {
+            // Battin series, the general expression loses precision near parabola
+            let eta = y - self.lambda * x;
+            let s1 = 0.5 * (1.0 - self.lambda - x * eta);
+            let q = 4.0 / 3.0 * hypergeometric_f(s1);
+            (eta.powf(3.0) * q + 4.0 * self.lambda * eta) / 2.0
+                + m_pi / (x.powf(2.0) - 1.0).abs().powf(1.5)
+        } else {
+            1.0 / (1.0 - x.powf(2.0))
+                * ((self.psi(x, y) + m_pi) / (1.0 - x.powf(2.0)).abs().sqrt() - x + self.lambda * y)
         }
+    }
 
-        let tol = 1e-08;
-
-        // T if x = 0
-        let t_0 = self.lambda.acos() + self.lambda * (1.0 - self.lambda.powf(2.0)).sqrt();
-        // T if x = 1
-        let t_1 = 2.0 / 3.0 * (1.0 - self.lambda.powf(3.0));
-
-        // guess x based on non dimentional time-of-flight
-        self.x = if self.t_nd >= t_0 {
-            (t_0 / self.t_nd).powf(2.0 / 3.0) - 1.0
-        } else if self.t_nd < t_1 {
-            2.5 * t_1 * (t_1 - self.t_nd) / self.t_nd / (1.0 - self.lambda.powf(5.0)) + 1.0
-        } else {
-            (t_0 / self.t_nd).powf((t_1 / t_0).log2()) - 1.0
-        };
-        self.set_y();
+    fn time_derivatives(&self, x: f64, t: f64) -> (f64, f64, f64) {
+        // first, second and third derivatives of time-of-flight by x
+        let y = self.y_of(x);
+        let f_p = (3.0 * t * x - 2.0 + 2.0 * self.lambda.powf(3.0) * x / y) / (1.0 - x.powf(2.0));
+        let f_pp = (3.0 * t
+            + 5.0 * x * f_p
+            + 2.0 * (1.0 - self.lambda.powf(2.0)) * self.lambda.powf(3.0) / y.powf(3.0))
+            / (1.0 - x.powf(2.0));
+        let f_ppp = (7.0 * x * f_pp + 8.0 * f_p
+            - 6.0 * (1.0 - self.lambda.powf(2.0)) * self.lambda.powf(5.0) * x / y.powf(5.0))
+            / (1.0 - x.powf(2.0));
+        (f_p, f_pp, f_ppp)
+    }
 
+    fn solution(
+        &self,
+        x0: f64,
+        revolutions: u32,
+        branch: Option<LambertBranch>,
+    ) -> Result<LambertSolution, LambertError> {
         // compute x using householder method
-
-        let mut delta_x: f64 = 2.0 * tol;
-        let mut iterations = 10;
-        while delta_x.abs() > tol && iterations > 0 {
-            let t = 1.0 / (1.0 - self.x.powf(2.0))
-                * (self.psi() / (1.0 - self.x.powf(2.0)).abs().sqrt() - self.x
-                    + self.lambda * self.y);
+        let mut x = x0;
+        let mut delta_x: f64 = 2.0 * self.tolerance;
+        let mut iterations = 0;
+        while delta_x.abs() > self.tolerance && iterations < self.max_iterations {
+            let t = self.time_of_flight(x, revolutions);
             let f_n = t - self.t_nd;
-            let f_p = (3.0 * t * self.x - 2.0 + 2.0 * self.lambda.powf(3.0) * self.x / self.y)
-                / (1.0 - self.x.powf(2.0));
-            let f_pp = (3.0 * t
-                + 5.0 * self.x * f_p
-                + 2.0 * (1.0 - self.lambda.powf(2.0)) * self.lambda.powf(3.0) / self.y.powf(3.0))
-                / (1.0 - self.x.powf(2.0));
-            let f_ppp = (7.0 * self.x * f_pp + 8.0 * f_p
-                - 6.0 * (1.0 - self.lambda.powf(2.0)) * self.lambda.powf(5.0) * self.x
-                    / self.y.powf(5.0))
-                / (1.0 - self.x.powf(2.0));
+            let (f_p, f_pp, f_ppp) = self.time_derivatives(x, t);
             delta_x = f_n * (f_p.powf(2.0) - f_n * f_pp / 2.0)
                 / (f_p * (f_p.powf(2.0) - f_n * f_pp) + f_ppp * f_n.powf(2.0) / 6.0);
-            self.x -= delta_x;
-            self.set_y();
-            iterations -= 1;
+            x -= delta_x;
+            iterations += 1;
         }
-        self.status = if iterations > 0 {
-            LambertSolverStatus::Converged
-        } else {
-            LambertSolverStatus::NotConverged
+        let delta_x = delta_x.abs();
+        // time-of-flight residual in seconds
+        let residual = (self.time_of_flight(x, revolutions) - self.t_nd)
+            / (2.0 * self.mu / self.s.powf(3.0)).sqrt();
+        if delta_x.is_nan() || delta_x > self.tolerance {
+            return Err(LambertError::NotConverged {
+                iterations,
+                last_step: delta_x,
+                residual,
+            });
+        }
+        let (v1, v2) = self.velocity(x);
+        Ok(LambertSolution {
+            v1,
+            v2,
+            revolutions,
+            branch,
+            iterations,
+            last_step: delta_x,
+            residual,
+            warning: self.warning,
+        })
+    }
+
+    fn multi_revolution_solution(
+        &self,
+        revolutions: u32,
+        branch: LambertBranch,
+    ) -> Result<LambertSolution, LambertError> {
+        // one of the two solutions with given number of full revolutions
+        let t_min = self.min_time_of_flight(revolutions);
+        if t_min > self.t_nd {
+            return Err(LambertError::TooManyRevolutions {
+                revolutions,
+                min_tof: t_min / (2.0 * self.mu / self.s.powf(3.0)).sqrt(),
+            });
+        }
+        let m_pi = f64::from(revolutions) * PI;
+        let x0 = match branch {
+            LambertBranch::Left => {
+                let tmp = ((m_pi + PI) / (8.0 * self.t_nd)).powf(2.0 / 3.0);
+                (tmp - 1.0) / (tmp + 1.0)
+            }
+            LambertBranch::Right => {
+                let tmp = (8.0 * self.t_nd / m_pi).powf(2.0 / 3.0);
+                (tmp - 1.0) / (tmp + 1.0)
+            }
         };
-        Ok(())
+        self.solution(x0, revolutions, Some(branch))
     }
 
-    fn calculate_velocity(&mut self) {
+    fn min_time_of_flight(&self, revolutions: u32) -> f64 {
+        // minimal time-of-flight with given number of revolutions, found with Halley method
+        let mut x = 0.0;
+        let mut t_min = self.time_of_flight(x, revolutions);
+        for _ in 0..MAX_ITERATIONS {
+            let (f_p, f_pp, f_ppp) = self.time_derivatives(x, t_min);
+            if f_p == 0.0 {
+                break;
+            }
+            let delta_x = f_p * f_pp / (f_pp.powf(2.0) - f_p * f_ppp / 2.0);
+            x -= delta_x;
+            t_min = self.time_of_flight(x, revolutions);
+            if delta_x.abs() < TOLERANCE {
+                break;
+            }
+        }
+        t_min
+    }
+
+    fn velocity(&self, x: f64) -> (Vector3D, Vector3D) {
+        let y = self.y_of(x);
         let gamma = (self.mu * self.s / 2.0).sqrt();
         let rho = (self.r1 - self.r2) / self.c;
         let sigma = (1.0 - rho.powf(2.0)).sqrt();
 
-        let v_r1 = gamma * (self.lambda * self.y - self.x - rho * (self.lambda * self.y + self.x))
-            / self.r1;
-        let v_r2 = -gamma * (self.lambda * self.y - self.x + rho * (self.lambda * self.y + self.x))
-            / self.r2;
-        let v_t1 = gamma * sigma * (self.y + self.lambda * self.x) / self.r1;
-        let v_t2 = gamma * sigma * (self.y + self.lambda * self.x) / self.r2;
+        let v_r1 = gamma * (self.lambda * y - x - rho * (self.lambda * y + x)) / self.r1;
+        let v_r2 = -gamma * (self.lambda * y - x + rho * (self.lambda * y + x)) / self.r2;
+        let v_t1 = gamma * sigma * (y + self.lambda * x) / self.r1;
+        let v_t2 = gamma * sigma * (y + self.lambda * x) / self.r2;
+
+        (
+            v_r1 * self.i_r1 + v_t1 * self.i_t1,
+            v_r2 * self.i_r2 + v_t2 * self.i_t2,
+        )
+    }
+}
+
+// Impulses of a transfer between the departure and arrival orbits (km/s)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DeltaV {
+    // v1 - v_dep, onto the transfer at r1
+    pub departure: Vector3D,
+    // v_arr - v2, onto the target orbit at r2
+    pub arrival: Vector3D,
+}
+
+impl DeltaV {
+    pub fn total(&self) -> f64 {
+        // both impulses of a rendezvous
+        self.departure.mag() + self.arrival.mag()
+    }
+
+    pub fn v_inf(&self) -> Vector3D {
+        // arrival velocity relative to the target when flying by without the
+        // arrival impulse
+        -self.arrival
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "gui", derive(Data))]
+pub enum LambertSolverStatus {
+    #[default]
+    Initialized,
+    Solved(LambertSolution),
+    Failed(LambertError),
+}
+
+// Lambert problem as edited in the GUI, solved with `solve`
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LambertSolver {
+    // first radius-vector
+    #[cfg_attr(feature = "serde", serde(rename = "r1"))]
+    r1_v: Vector3D,
+
+    // second radius-vector
+    #[cfg_attr(feature = "serde", serde(rename = "r2"))]
+    r2_v: Vector3D,
+
+    // time of flight
+    t: f64,
+
+    // gravitational body constant
+    mu: f64,
+
+    #[cfg_attr(feature = "serde", serde(default))]
+    options: LambertOptions,
+
+    // radius of the central body, only used for the displayed altitudes
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub body_radius: f64,
+
+    // velocities of the spacecraft before departure at r1 and of the target at r2,
+    // zero if unknown, e.g. to depart from rest
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub v_dep: Vector3D,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub v_arr: Vector3D,
+
+    // Output parameters, not serialized: loaded problems start out Initialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub status: LambertSolverStatus,
+}
+
+impl LambertSolver {
+    pub fn new(r1: Vector3D, r2: Vector3D, t: f64, mu: f64) -> Result<Self, LambertError> {
+        Self::with_options(r1, r2, t, mu, LambertOptions::default())
+    }
+
+    pub fn with_options(
+        r1: Vector3D,
+        r2: Vector3D,
+        t: f64,
+        mu: f64,
+        options: LambertOptions,
+    ) -> Result<Self, LambertError> {
+        let solution = solve(r1, r2, t, mu, &options)?;
+        Ok(Self {
+            r1_v: r1,
+            r2_v: r2,
+            t,
+            mu,
+            options,
+            body_radius: 0.0,
+            v_dep: Vector3D::default(),
+            v_arr: Vector3D::default(),
+            status: LambertSolverStatus::Solved(solution),
+        })
+    }
 
-        self.v1 = v_r1 * self.i_r1 + v_t1 * self.i_t1;
-        self.v2 = v_r2 * self.i_r2 + v_t2 * self.i_t2;
+    pub fn set_central_body(&mut self, body: &Body) {
+        // takes mu and the body radius from the catalogue and solves again
+        self.mu = body.mu;
+        self.body_radius = body.radius;
+        self.recalculate_solution();
+    }
+
+    pub fn get_r1(&self) -> Vector3D {
+        self.r1_v
+    }
+
+    pub fn get_r2(&self) -> Vector3D {
+        self.r2_v
+    }
+
+    pub fn get_tof(&self) -> f64 {
+        self.t
+    }
+
+    pub fn get_mu(&self) -> f64 {
+        self.mu
+    }
+
+    pub fn get_options(&self) -> LambertOptions {
+        self.options
+    }
+
+    pub fn set_transfer(&mut self, r1: Vector3D, r2: Vector3D, t: f64) {
+        // replaces the radius-vectors and time of flight and solves again
+        self.r1_v = r1;
+        self.r2_v = r2;
+        self.t = t;
+        self.recalculate_solution();
+    }
+
+    pub fn set_departure_orbit(&mut self, orbit: &Orbit) {
+        // departs from the current position on the orbit, replacing r1 and v_dep
+        let (r, v) = orbit.to_rv(self.mu);
+        self.r1_v = r;
+        self.v_dep = v;
+        self.recalculate_solution();
+    }
+
+    pub fn set_arrival_orbit(&mut self, orbit: &Orbit) {
+        // target at its current position on the orbit, replacing r2 and v_arr
+        let (r, v) = orbit.to_rv(self.mu);
+        self.r2_v = r;
+        self.v_arr = v;
+        self.recalculate_solution();
     }
 
     pub fn recalculate_solution(&mut self) {
-        self.status = LambertSolverStatus::Initialized;
-        match self.calculate_params() {
-            Ok(_) => (),
-            Err(e) => self.status = e,
+        self.status = match solve(self.r1_v, self.r2_v, self.t, self.mu, &self.options) {
+            Ok(solution) => LambertSolverStatus::Solved(solution),
+            Err(e) => LambertSolverStatus::Failed(e),
+        }
+    }
+
+    pub fn get_solution(&self) -> Option<&LambertSolution> {
+        match &self.status {
+            LambertSolverStatus::Solved(solution) => Some(solution),
+            _ => None,
         }
     }
 
     pub fn get_v1(&self) -> Vector3D {
-        self.v1
+        self.get_solution().map(|s| s.v1).unwrap_or_default()
     }
 
     pub fn get_v2(&self) -> Vector3D {
-        self.v2
+        self.get_solution().map(|s| s.v2).unwrap_or_default()
+    }
+
+    pub fn get_delta_v(&self) -> Option<DeltaV> {
+        self.get_solution().map(|s| DeltaV {
+            departure: s.v1 - self.v_dep,
+            arrival: self.v_arr - s.v2,
+        })
     }
 
     pub fn get_orbit(&self) -> Orbit {
-        Orbit::from_rv(self.r2_v, self.v2, self.mu)
+        Orbit::from_rv(self.r2_v, self.get_v2(), self.mu)
+    }
+}
+
+fn hypergeometric_f(z: f64) -> f64 {
+    // Gauss hypergeometric function 2F1(3, 1, 5/2, z) used by Battin series
+    let mut sum = 1.0;
+    let mut term: f64 = 1.0;
+    let mut j = 0.0;
+    while term.abs() > 1e-11 {
+        term *= (3.0 + j) * (1.0 + j) / (2.5 + j) * z / (j + 1.0);
+        sum += term;
+        j += 1.0;
     }
+    sum
 }
 
 #[cfg(test)]
@@ -276,10 +879,11 @@ fn test_orbit() {
     let orbit_ans = Orbit {
         ecc: 0.43348753093376213,
         a: 20002.887624230483,
-        inc: 0.8643534138360562,
+        inc: 0.5269331332631371,
         lan: 0.7784202841672526,
         argp: 0.5359234295374832,
         nu: 1.5903847969354517,
+        ..Default::default()
     };
     assert!((orbit.ecc - orbit_ans.ecc).abs() < 0.001);
     assert!((orbit.a - orbit_ans.a).abs() < 0.001);
@@ -288,3 +892,468 @@ fn test_orbit() {
     assert!((orbit.argp - orbit_ans.argp).abs() < 0.001);
     assert!((orbit.nu - orbit_ans.nu).abs() < 0.001);
 }
+
+#[cfg(test)]
+fn elliptic_transfer_time(r1: Vector3D, v1: Vector3D, r2: Vector3D, mu: f64, revs: u32) -> f64 {
+    // time to travel from r1 to r2 along the orbit defined by r1, v1
+    let a = 1.0 / (2.0 / r1.mag() - v1 * v1 / mu);
+    let ecc_v = v1.cross(r1.cross(v1)) / mu - r1.norm();
+    let ecc = ecc_v.mag();
+    let h = r1.cross(v1);
+    let mean_anomaly = |r: Vector3D| {
+        let mut nu = (ecc_v * r / ecc / r.mag()).clamp(-1.0, 1.0).acos();
+        if ecc_v.cross(r) * h < 0.0 {
+            nu = 2.0 * PI - nu;
+        }
+        let e = 2.0 * (((1.0 - ecc) / (1.0 + ecc)).sqrt() * (nu / 2.0).tan()).atan();
+        e - ecc * e.sin()
+    };
+    let mut dm = mean_anomaly(r2) - mean_anomaly(r1);
+    if dm < 0.0 {
+        dm += 2.0 * PI;
+    }
+    (a.powf(3.0) / mu).sqrt() * (dm + 2.0 * PI * f64::from(revs))
+}
+
+#[test]
+fn test_long_single_revolution() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let r2 = Vector3D::new(-14600.0, 2500.0, 7000.0);
+    let sol = solve(r1, r2, 20000.0, mu, &LambertOptions::default()).unwrap();
+    let t = elliptic_transfer_time(r1, sol.v1, r2, mu, 0);
+    assert!((t - 20000.0).abs() < 0.01);
+}
+
+#[test]
+fn test_multi_revolution() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let r2 = Vector3D::new(-14600.0, 2500.0, 7000.0);
+    let tof = 200000.0;
+    let solutions = solve_multi_revolution(r1, r2, tof, mu, 3, &LambertOptions::default()).unwrap();
+    assert_eq!(solutions.len(), 6);
+    for (i, sol) in solutions.iter().enumerate() {
+        let sol = sol.as_ref().unwrap();
+        assert_eq!(sol.revolutions, i as u32 / 2 + 1);
+        // both ends lie on the same orbit
+        assert!((r1.cross(sol.v1) - r2.cross(sol.v2)).mag() < 1e-6);
+        let t = elliptic_transfer_time(r1, sol.v1, r2, mu, sol.revolutions);
+        assert!((t - tof).abs() < 0.1);
+    }
+    let left = solutions[0].as_ref().unwrap();
+    let right = solutions[1].as_ref().unwrap();
+    assert_eq!(left.branch, Some(LambertBranch::Left));
+    assert_eq!(right.branch, Some(LambertBranch::Right));
+    assert!((left.v1 - right.v1).mag() > 0.01);
+}
+
+#[test]
+fn test_multi_revolution_too_short() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let r2 = Vector3D::new(-14600.0, 2500.0, 7000.0);
+    let options = LambertOptions::default();
+    assert!(solve_multi_revolution(r1, r2, 3600.0, mu, 3, &options)
+        .unwrap()
+        .is_empty());
+    assert!(
+        solve_multi_revolution(r1, r2, 200000.0, mu, 100, &options)
+            .unwrap()
+            .len()
+            < 200
+    );
+}
+
+#[test]
+fn test_solver_revolutions() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let r2 = Vector3D::new(-14600.0, 2500.0, 7000.0);
+    let tof = 200000.0;
+    let solutions = solve_multi_revolution(r1, r2, tof, mu, 2, &LambertOptions::default()).unwrap();
+    let mut options = LambertOptions {
+        revolutions: 2,
+        branch: LambertBranch::Right,
+        ..Default::default()
+    };
+    let ls = LambertSolver::with_options(r1, r2, tof, mu, options).unwrap();
+    assert_eq!(ls.get_solution(), solutions[3].as_ref().ok());
+    options.branch = LambertBranch::Left;
+    assert_eq!(
+        solve(r1, r2, tof, mu, &options).as_ref(),
+        solutions[2].as_ref()
+    );
+
+    // too short for two revolutions
+    let min_tof = match solve(r1, r2, 3600.0, mu, &options) {
+        Err(LambertError::TooManyRevolutions {
+            revolutions: 2,
+            min_tof,
+        }) => min_tof,
+        other => panic!("unexpected {:?}", other),
+    };
+    assert!(min_tof > 3600.0 && min_tof < tof);
+
+    // the other algorithms have no multi-revolution branch
+    assert_eq!(
+        UniversalVariables.solve(r1, r2, tof, mu, &options),
+        Err(LambertError::InvalidInput {
+            parameter: "revolutions",
+            value: 2.0
+        })
+    );
+}
+
+#[test]
+fn test_transfer_direction() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let r2 = Vector3D::new(-14600.0, 2500.0, 7000.0);
+    let with_direction = |direction| {
+        let options = LambertOptions {
+            direction,
+            ..Default::default()
+        };
+        solve(r1, r2, 3600.0, mu, &options).unwrap()
+    };
+    let prograde = with_direction(TransferDirection::Prograde);
+    let short_way = with_direction(TransferDirection::ShortWay);
+    let retrograde = with_direction(TransferDirection::Retrograde);
+    let long_way = with_direction(TransferDirection::LongWay);
+    assert!(r1.cross(prograde.v1).z > 0.0);
+    assert!(r1.cross(retrograde.v1).z < 0.0);
+    assert!((prograde.v1 - short_way.v1).mag() < 1e-9);
+    assert!((retrograde.v1 - long_way.v1).mag() < 1e-9);
+    let t = elliptic_transfer_time(r1, retrograde.v1, r2, mu, 0);
+    assert!((t - 3600.0).abs() < 0.01);
+}
+
+#[test]
+fn test_polar_transfer() {
+    // transfer plane contains Z axis, prograde and retrograde are undefined
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(7000.0, 0.0, 0.0);
+    let r2 = Vector3D::new(0.0, 0.0, 8000.0);
+    let short_way = LambertOptions {
+        direction: TransferDirection::ShortWay,
+        ..Default::default()
+    };
+    let long_way = LambertOptions {
+        direction: TransferDirection::LongWay,
+        ..Default::default()
+    };
+    let short_way = solve(r1, r2, 2000.0, mu, &short_way).unwrap();
+    let long_way = solve(r1, r2, 6000.0, mu, &long_way).unwrap();
+    assert!(short_way.v1.z > 0.0);
+    assert!(long_way.v1.z < 0.0);
+    let t = elliptic_transfer_time(r1, long_way.v1, r2, mu, 0);
+    assert!((t - 6000.0).abs() < 0.01);
+}
+
+#[test]
+fn test_errors() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let r2 = Vector3D::new(-14600.0, 2500.0, 7000.0);
+    let options = LambertOptions::default();
+    assert_eq!(
+        solve(r1, r2, -1.0, mu, &options),
+        Err(LambertError::InvalidInput {
+            parameter: "time-of-flight",
+            value: -1.0
+        })
+    );
+    assert!(matches!(
+        solve(r1, 2.0 * r1, 3600.0, mu, &options),
+        Err(LambertError::CollinearVectors { .. })
+    ));
+    assert!(matches!(
+        LambertSolver::new(r1, r2, 3600.0, f64::NAN),
+        Err(LambertError::InvalidInput {
+            parameter: "mu",
+            ..
+        })
+    ));
+
+    let mut ls = LambertSolver::new(r1, r2, 3600.0, mu).unwrap();
+    ls.t = 0.0;
+    ls.recalculate_solution();
+    assert!(ls.get_solution().is_none());
+    assert!(matches!(ls.status, LambertSolverStatus::Failed(_)));
+
+    ls.set_transfer(r1, r2, 3600.0);
+    assert!(ls.get_solution().is_some());
+}
+
+#[test]
+fn test_options() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let r2 = Vector3D::new(-14600.0, 2500.0, 7000.0);
+    let reference = solve(r1, r2, 3600.0, mu, &LambertOptions::default()).unwrap();
+    assert!(reference.last_step <= TOLERANCE);
+    assert!(reference.residual.abs() < 1e-6);
+    assert!(reference.iterations > 0 && reference.iterations <= MAX_ITERATIONS);
+
+    for &initial_guess in &[InitialGuess::Rational, InitialGuess::Fixed(0.0)] {
+        let options = LambertOptions {
+            initial_guess,
+            tolerance: 1e-12,
+            ..Default::default()
+        };
+        let sol = solve(r1, r2, 3600.0, mu, &options).unwrap();
+        assert!(sol.last_step <= 1e-12);
+        assert!((sol.v1 - reference.v1).mag() < 1e-6);
+    }
+
+    let options = LambertOptions {
+        max_iterations: 1,
+        initial_guess: InitialGuess::Fixed(0.9),
+        ..Default::default()
+    };
+    match solve(r1, r2, 3600.0, mu, &options) {
+        Err(LambertError::NotConverged {
+            iterations,
+            last_step,
+            residual,
+        }) => {
+            assert_eq!(iterations, 1);
+            assert!(last_step > TOLERANCE);
+            assert!(residual.abs() > 0.0);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    let options = LambertOptions {
+        tolerance: 0.0,
+        ..Default::default()
+    };
+    assert!(matches!(
+        solve(r1, r2, 3600.0, mu, &options),
+        Err(LambertError::InvalidInput {
+            parameter: "tolerance",
+            ..
+        })
+    ));
+}
+
+#[cfg(test)]
+fn assert_algorithms_agree(r1: Vector3D, r2: Vector3D, tof: f64, options: &LambertOptions) {
+    let mu = 3.986004e5;
+    let reference = Izzo.solve(r1, r2, tof, mu, options).unwrap();
+    let algorithms: [&dyn LambertAlgorithm; 2] = [&UniversalVariables, &Gooding];
+    for algorithm in &algorithms {
+        let sol = algorithm.solve(r1, r2, tof, mu, options).unwrap();
+        assert!((sol.v1 - reference.v1).mag() < 1e-6);
+        assert!((sol.v2 - reference.v2).mag() < 1e-6);
+        assert!(sol.residual.abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_algorithms_agree() {
+    let options = LambertOptions::default();
+    // test_velocity case
+    assert_algorithms_agree(
+        Vector3D::new(5000.0, 10000.0, 2100.0),
+        Vector3D::new(-14600.0, 2500.0, 7000.0),
+        3600.0,
+        &options,
+    );
+    // hyperbolic
+    assert_algorithms_agree(
+        Vector3D::new(7000.0, 0.0, 0.0),
+        Vector3D::new(0.0, 8000.0, 500.0),
+        600.0,
+        &options,
+    );
+    // near-parabolic
+    assert_algorithms_agree(
+        Vector3D::new(7000.0, 0.0, 0.0),
+        Vector3D::new(-3000.0, 20000.0, 0.0),
+        3300.0,
+        &options,
+    );
+    // near 180 degrees
+    assert_algorithms_agree(
+        Vector3D::new(7000.0, 0.0, 0.0),
+        Vector3D::new(-8000.0, 10.0, 0.0),
+        3000.0,
+        &options,
+    );
+    assert_algorithms_agree(
+        Vector3D::new(7000.0, 0.0, 0.0),
+        Vector3D::new(-8000.0, -10.0, 1.0),
+        3000.0,
+        &options,
+    );
+    // long way
+    let options = LambertOptions {
+        direction: TransferDirection::LongWay,
+        ..Default::default()
+    };
+    assert_algorithms_agree(
+        Vector3D::new(5000.0, 10000.0, 2100.0),
+        Vector3D::new(-14600.0, 2500.0, 7000.0),
+        15000.0,
+        &options,
+    );
+}
+
+#[test]
+fn test_hohmann_geometry() {
+    // exact 180 degree transfer from LEO to GEO radius in the equatorial plane
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(7000.0, 0.0, 0.0);
+    let r2 = Vector3D::new(-42164.0, 0.0, 0.0);
+    let a: f64 = (7000.0 + 42164.0) / 2.0;
+    let tof = PI * (a.powf(3.0) / mu).sqrt();
+    let options = LambertOptions {
+        normal: Some(Vector3D::new(0.0, 0.0, 1.0)),
+        ..Default::default()
+    };
+    assert!(matches!(
+        solve(r1, r2, tof, mu, &LambertOptions::default()),
+        Err(LambertError::CollinearVectors { .. })
+    ));
+
+    let v_p = (mu * (2.0 / 7000.0 - 1.0 / a)).sqrt();
+    let v_a = (mu * (2.0 / 42164.0 - 1.0 / a)).sqrt();
+    let algorithms: [&dyn LambertAlgorithm; 3] = [&Izzo, &UniversalVariables, &Gooding];
+    for algorithm in &algorithms {
+        let sol = algorithm.solve(r1, r2, tof, mu, &options).unwrap();
+        assert!((sol.v1 - Vector3D::new(0.0, v_p, 0.0)).mag() < 1e-6);
+        assert!((sol.v2 - Vector3D::new(0.0, -v_a, 0.0)).mag() < 1e-6);
+        assert_eq!(sol.warning, None);
+
+        // faster and slower transfers leave r1 with radial velocity
+        for &factor in &[0.6, 1.5] {
+            let izzo = solve(r1, r2, factor * tof, mu, &options).unwrap();
+            let sol = algorithm.solve(r1, r2, factor * tof, mu, &options).unwrap();
+            assert!(
+                (sol.v1 - izzo.v1).mag() < 1e-6,
+                "{:?} {:?}",
+                sol.v1,
+                izzo.v1
+            );
+            assert!((sol.v2 - izzo.v2).mag() < 1e-6);
+            assert!(izzo.v1.x.abs() > 0.1);
+        }
+    }
+
+    // retrograde transfer along -Z
+    let options = LambertOptions {
+        normal: Some(Vector3D::new(0.0, 0.0, -1.0)),
+        ..Default::default()
+    };
+    let sol = solve(r1, r2, tof, mu, &options).unwrap();
+    assert!((sol.v1 - Vector3D::new(0.0, -v_p, 0.0)).mag() < 1e-6);
+}
+
+#[test]
+fn test_near_collinear() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(7000.0, 0.0, 0.0);
+    let r2 = Vector3D::new(-42164.0, 1.0, 1.0);
+    let tof = 19000.0;
+    let sol = solve(r1, r2, tof, mu, &LambertOptions::default()).unwrap();
+    assert!(matches!(
+        sol.warning,
+        Some(LambertWarning::NearCollinear { transfer_angle }) if PI - transfer_angle < 0.01
+    ));
+
+    // the given normal keeps the transfer in the equatorial plane
+    let options = LambertOptions {
+        normal: Some(Vector3D::new(0.0, 0.0, 1.0)),
+        ..Default::default()
+    };
+    let sol = solve(r1, r2, tof, mu, &options).unwrap();
+    assert_eq!(sol.warning, None);
+    assert!(sol.v1.z.abs() < 1e-3);
+
+    let options = LambertOptions {
+        collinearity_threshold: 0.0,
+        ..Default::default()
+    };
+    let sol = solve(r1, r2, tof, mu, &options).unwrap();
+    assert_eq!(sol.warning, None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(7000.0, 0.0, 0.0);
+    let r2 = Vector3D::new(-42164.0, 1.0, 1.0);
+    let options = LambertOptions {
+        direction: TransferDirection::LongWay,
+        initial_guess: InitialGuess::Fixed(0.5),
+        ..Default::default()
+    };
+    let ls = LambertSolver::with_options(r1, r2, 19000.0, mu, options).unwrap();
+    let json = serde_json::to_string(&ls).unwrap();
+    assert!(json.contains("\"r1\":{\"x\":7000.0,\"y\":0.0,\"z\":0.0}"));
+    assert!(!json.contains("status"));
+
+    let mut loaded: LambertSolver = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.status, LambertSolverStatus::Initialized);
+    assert_eq!(loaded.options, options);
+    loaded.recalculate_solution();
+    assert_eq!(loaded.get_solution(), ls.get_solution());
+
+    let solution = ls.get_solution().unwrap();
+    let json = serde_json::to_string(solution).unwrap();
+    assert_eq!(
+        &serde_json::from_str::<LambertSolution>(&json).unwrap(),
+        solution
+    );
+
+    // options and body radius are optional
+    let loaded: LambertSolver = serde_json::from_str(
+        r#"{"r1": {"x": 7000, "y": 0, "z": 0}, "r2": {"x": 0, "y": 7000, "z": 0}, "t": 1000, "mu": 3.986004e5}"#,
+    )
+    .unwrap();
+    assert_eq!(loaded.options, LambertOptions::default());
+    assert_eq!(loaded.body_radius, 0.0);
+}
+
+#[test]
+fn test_delta_v() {
+    // Hohmann transfer from a circular LEO to GEO, about 3.77 km/s in total
+    let mu = 3.986004e5;
+    let options = LambertOptions {
+        normal: Some(Vector3D::new(0.0, 0.0, 1.0)),
+        ..Default::default()
+    };
+    let a: f64 = (7000.0 + 42164.0) / 2.0;
+    let tof = PI * (a.powf(3.0) / mu).sqrt();
+    let mut ls = LambertSolver::with_options(
+        Vector3D::new(7000.0, 0.0, 0.0),
+        Vector3D::new(0.0, 42164.0, 0.0),
+        tof,
+        mu,
+        options,
+    )
+    .unwrap();
+    // departing from rest
+    let dv = ls.get_delta_v().unwrap();
+    assert_eq!(dv.departure, ls.get_v1());
+    assert_eq!(dv.v_inf(), ls.get_v2());
+
+    ls.set_departure_orbit(&Orbit::new(7000.0, 0.0, 0.0, 0.0, 0.0, 0.0, mu));
+    ls.set_arrival_orbit(&Orbit::new(42164.0, 0.0, 0.0, 0.0, 0.0, PI, mu));
+    assert!((ls.get_r2() - Vector3D::new(-42164.0, 0.0, 0.0)).mag() < 1e-6);
+    assert!((ls.v_dep - Vector3D::new(0.0, (mu / 7000.0).sqrt(), 0.0)).mag() < 1e-9);
+
+    let dv = ls.get_delta_v().unwrap();
+    let dv1 = (mu * (2.0 / 7000.0 - 1.0 / a)).sqrt() - (mu / 7000.0).sqrt();
+    let dv2 = (mu / 42164.0).sqrt() - (mu * (2.0 / 42164.0 - 1.0 / a)).sqrt();
+    assert!((dv.departure - Vector3D::new(0.0, dv1, 0.0)).mag() < 1e-6);
+    assert!((dv.arrival - Vector3D::new(0.0, -dv2, 0.0)).mag() < 1e-6);
+    assert!((dv.total() - 3.771).abs() < 1e-3, "{}", dv.total());
+    assert!((dv.v_inf().mag() - dv2).abs() < 1e-6);
+
+    ls.set_transfer(ls.get_r1(), ls.get_r2(), -1.0);
+    assert_eq!(ls.get_delta_v(), None);
+}
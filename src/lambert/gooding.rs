@@ -0,0 +1,262 @@
+// Gooding's method (Celestial Mechanics 48, 1990), single revolution case
+use std::f64::consts::PI;
+
+use super::{
+    check_inputs, check_single_revolution, transfer_plane, LambertAlgorithm, LambertError,
+    LambertOptions, LambertSolution,
+};
+use crate::Vector3D;
+
+// |1 - x^2| below which time-of-flight is computed by series
+const SERIES_RANGE: f64 = 0.4;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gooding;
+
+impl LambertAlgorithm for Gooding {
+    fn solve(
+        &self,
+        r1_v: Vector3D,
+        r2_v: Vector3D,
+        tof: f64,
+        mu: f64,
+        options: &LambertOptions,
+    ) -> Result<LambertSolution, LambertError> {
+        check_inputs(r1_v, r2_v, tof, mu, options)?;
+        check_single_revolution(options)?;
+        let r1 = r1_v.mag();
+        let r2 = r2_v.mag();
+        let plane = transfer_plane(r1_v, r2_v, options)?;
+        let i_h = plane.i_h;
+
+        // half of the transfer angle
+        let theta = (r1_v * r2_v / r1 / r2).clamp(-1.0, 1.0).acos();
+        let half_theta = if plane.long_way {
+            PI - theta / 2.0
+        } else {
+            theta / 2.0
+        };
+
+        let dr = r1 - r2;
+        let r1r2 = r1 * r2;
+        let r1r2th = 4.0 * r1r2 * half_theta.sin().powf(2.0);
+        let csq = dr.powf(2.0) + r1r2th;
+        let c = csq.sqrt();
+        let s = (r1 + r2 + c) / 2.0;
+        let gms = (mu * s / 2.0).sqrt();
+        let params = Params {
+            q: r1r2.sqrt() * half_theta.cos() / s,
+            qsqfm1: c / s,
+        };
+        let rho = dr / c;
+        let sig = r1r2th / csq;
+
+        // non dimensional time-of-flight
+        let t_nd = 4.0 * gms * tof / s.powf(2.0);
+
+        // initial guess from T at x = 0 and bilinear approximation
+        let thr2 = params.qsqfm1.atan2(2.0 * params.q) / PI;
+        let t_0 = params.tlamb(0.0).t;
+        let t_diff = t_nd - t_0;
+        let mut x = if t_diff <= 0.0 {
+            // -4 is dT/dx at x = 0
+            t_0 * t_diff / (-4.0 * t_nd)
+        } else {
+            let mut x = -t_diff / (t_diff + 4.0);
+            let w = x + 1.7 * (2.0 * (1.0 - thr2)).sqrt();
+            if w < 0.0 {
+                x -=
+                    (-w).sqrt().sqrt().sqrt().sqrt() * (x + (t_diff / (t_diff + 1.5 * t_0)).sqrt());
+            }
+            let w = 4.0 / (4.0 + t_diff);
+            x * (1.0 + x * (0.5 * w - 0.03 * x * w.sqrt()))
+        };
+
+        // Halley iterations
+        let mut delta_x: f64 = 2.0 * options.tolerance;
+        let mut iterations = 0;
+        while delta_x.abs() > options.tolerance && iterations < options.max_iterations {
+            let tl = params.tlamb(x);
+            let f_n = t_nd - tl.t;
+            delta_x = if tl.dt != 0.0 {
+                f_n * tl.dt / (tl.dt.powf(2.0) + f_n * tl.d2t / 2.0)
+            } else {
+                0.0
+            };
+            x += delta_x;
+            iterations += 1;
+        }
+
+        let delta_x = delta_x.abs();
+        let residual = (params.tlamb(x).t - t_nd) / (4.0 * gms / s.powf(2.0));
+        if delta_x.is_nan() || delta_x > options.tolerance {
+            return Err(LambertError::NotConverged {
+                iterations,
+                last_step: delta_x,
+                residual,
+            });
+        }
+
+        // radial and transverse velocity components
+        let (qzminx, qzplx, zplqx) = params.velocity_terms(x);
+        let v_t = gms * zplqx * sig.sqrt();
+        let v_r1 = gms * (qzminx - qzplx * rho) / r1;
+        let v_r2 = -gms * (qzminx + qzplx * rho) / r2;
+
+        let i_r1 = r1_v.norm();
+        let i_r2 = r2_v.norm();
+        Ok(LambertSolution {
+            v1: v_r1 * i_r1 + v_t / r1 * i_h.cross(i_r1).norm(),
+            v2: v_r2 * i_r2 + v_t / r2 * i_h.cross(i_r2).norm(),
+            revolutions: 0,
+            branch: None,
+            iterations,
+            last_step: delta_x,
+            residual,
+            warning: plane.warning,
+        })
+    }
+}
+
+struct Params {
+    q: f64,
+    // 1 - q^2
+    qsqfm1: f64,
+}
+
+// non dimensional time-of-flight and its first two derivatives
+struct TimeOfFlight {
+    t: f64,
+    dt: f64,
+    d2t: f64,
+}
+
+impl Params {
+    fn intermediates(&self, x: f64) -> (f64, f64, f64, f64) {
+        // z, qx and Gooding's a and b with cancellation avoided
+        let qsq = self.q.powf(2.0);
+        let xsq = x.powf(2.0);
+        let u = (1.0 - x) * (1.0 + x);
+        let z = (self.qsqfm1 + qsq * xsq).sqrt();
+        let qx = self.q * x;
+        let (a, b) = if qx <= 0.0 {
+            (z - qx, self.q * z - x)
+        } else {
+            let aa = z + qx;
+            let bb = self.q * z + x;
+            (self.qsqfm1 / aa, self.qsqfm1 * (qsq * u - xsq) / bb)
+        };
+        (z, qx, a, b)
+    }
+
+    fn tlamb(&self, x: f64) -> TimeOfFlight {
+        let qsq = self.q.powf(2.0);
+        let xsq = x.powf(2.0);
+        let u = (1.0 - x) * (1.0 + x);
+        if x < 0.0 || u.abs() > SERIES_RANGE {
+            // direct computation
+            let y = u.abs().sqrt();
+            let (z, qx, a, b) = self.intermediates(x);
+            let g = if qx * u >= 0.0 {
+                x * z + self.q * u
+            } else {
+                (xsq - qsq * u) / (x * z - self.q * u)
+            };
+            let f = a * y;
+            let t = if x <= 1.0 {
+                f.atan2(g)
+            } else if f > SERIES_RANGE {
+                (f + g).ln()
+            } else {
+                // inverse tanh by series
+                let fg1 = f / (g + 1.0);
+                let fg1sq = fg1.powf(2.0);
+                let mut term = 2.0 * fg1;
+                let mut t = term;
+                let mut twoi1 = 1.0;
+                loop {
+                    twoi1 += 2.0;
+                    term *= fg1sq;
+                    let t_old = t;
+                    t += term / twoi1;
+                    if t == t_old {
+                        break t;
+                    }
+                }
+            };
+            let t = 2.0 * (t / y + b) / u;
+            let (dt, d2t) = if z != 0.0 {
+                let qz = self.q / z;
+                let qz3 = qz.powf(3.0);
+                let dt = (3.0 * x * t - 4.0 * (a + qx * self.qsqfm1) / z) / u;
+                let d2t = (3.0 * t + 5.0 * x * dt + 4.0 * qz3 * self.qsqfm1) / u;
+                (dt, d2t)
+            } else {
+                (0.0, 0.0)
+            };
+            TimeOfFlight { t, dt, d2t }
+        } else {
+            // series expansion around the parabola
+            let mut u0i = 1.0;
+            let mut u1i = 1.0;
+            let mut u2i = 1.0;
+            let mut term = 4.0;
+            let mut tq = self.q * self.qsqfm1;
+            let mut tqsum = if self.q < 0.5 {
+                1.0 - self.q * qsq
+            } else {
+                (1.0 / (1.0 + self.q) + self.q) * self.qsqfm1
+            };
+            let mut ttmold = term / 3.0;
+            let mut t = ttmold * tqsum;
+            let mut dt = 0.0;
+            let mut d2t = 0.0;
+            let mut i = 0;
+            loop {
+                i += 1;
+                let p = f64::from(i);
+                u0i *= u;
+                if i > 1 {
+                    u1i *= u;
+                }
+                if i > 2 {
+                    u2i *= u;
+                }
+                term *= (p - 0.5) / p;
+                tq *= qsq;
+                tqsum += tq;
+                let t_old = t;
+                let tterm = term / (2.0 * p + 3.0);
+                let tqterm = tterm * tqsum;
+                t -= u0i * ((1.5 * p + 0.25) * tqterm / (p * p - 0.25) - ttmold * tq);
+                ttmold = tterm;
+                let tqterm = tqterm * p;
+                dt += tqterm * u1i;
+                d2t += tqterm * u2i * (p - 1.0);
+                if i >= 2 && t == t_old {
+                    break;
+                }
+            }
+            let d2t = 2.0 * (2.0 * xsq * d2t - dt);
+            let dt = -2.0 * x * dt;
+            TimeOfFlight {
+                t: t / xsq,
+                dt,
+                d2t,
+            }
+        }
+    }
+
+    fn velocity_terms(&self, x: f64) -> (f64, f64, f64) {
+        // q * z - x, q * z + x and z + q * x
+        let qsq = self.q.powf(2.0);
+        let xsq = x.powf(2.0);
+        let u = (1.0 - x) * (1.0 + x);
+        let (z, qx, a, b) = self.intermediates(x);
+        if qx < 0.0 {
+            (b, self.qsqfm1 * (qsq * u - xsq) / b, self.qsqfm1 / a)
+        } else {
+            (b, self.q * z + x, z + qx)
+        }
+    }
+}
@@ -0,0 +1,190 @@
+// Universal variable formulation after Bate and Vallado
+use std::f64::consts::PI;
+
+use super::{
+    check_inputs, check_single_revolution, transfer_plane, LambertAlgorithm, LambertError,
+    LambertOptions, LambertSolution,
+};
+use crate::Vector3D;
+
+// below this |z| Stumpff functions are evaluated by series
+const SERIES_RANGE: f64 = 1e-3;
+
+// hyperbolic bracket search stops before cosh overflows
+const MAX_HYPERBOLIC_Z: f64 = 1e5;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniversalVariables;
+
+impl LambertAlgorithm for UniversalVariables {
+    fn solve(
+        &self,
+        r1_v: Vector3D,
+        r2_v: Vector3D,
+        tof: f64,
+        mu: f64,
+        options: &LambertOptions,
+    ) -> Result<LambertSolution, LambertError> {
+        check_inputs(r1_v, r2_v, tof, mu, options)?;
+        check_single_revolution(options)?;
+        let r1 = r1_v.mag();
+        let r2 = r2_v.mag();
+        let plane = transfer_plane(r1_v, r2_v, options)?;
+
+        let cos_dnu = r1_v * r2_v / r1 / r2;
+        let a = if plane.long_way { -1.0 } else { 1.0 } * (r1 * r2 * (1.0 + cos_dnu)).sqrt();
+        // at exactly 180 degrees, only possible with a given normal, g vanishes and
+        // the velocities are built in the plane of i_h instead
+        let opposite = r1_v.cross(r2_v).mag() == 0.0 || a == 0.0;
+        let a = if opposite { 0.0 } else { a };
+
+        let y = |z: f64| r1 + r2 + a * (z * stumpff_s(z) - 1.0) / stumpff_c(z).sqrt();
+        // time-of-flight for given z, zero where y is negative and the orbit does not exist
+        let time = |z: f64| {
+            let y = y(z);
+            if y < 0.0 {
+                return 0.0;
+            }
+            ((y / stumpff_c(z)).powf(1.5) * stumpff_s(z) + a * y.sqrt()) / mu.sqrt()
+        };
+
+        // z is bracketed between hyperbolic solutions and a full revolution
+        let mut z_up = 4.0 * PI.powf(2.0);
+        let mut z_low = -4.0 * PI;
+        while time(z_low) > tof && z_low > -MAX_HYPERBOLIC_Z {
+            z_up = z_low;
+            z_low *= 2.0;
+        }
+
+        // Newton iterations safeguarded by bisection
+        let mut z = 0.5 * (z_low + z_up);
+        let mut delta_z: f64 = 2.0 * options.tolerance;
+        let mut iterations = 0;
+        while delta_z.abs() > options.tolerance && iterations < options.max_iterations {
+            let y_z = y(z);
+            let f_n = time(z) - tof;
+            if f_n < 0.0 {
+                z_low = z;
+            } else {
+                z_up = z;
+            }
+            let z_new = if y_z > 0.0 {
+                z - f_n * mu.sqrt() / time_derivative(z, y_z, a)
+            } else {
+                f64::NAN
+            };
+            let z_new = if z_new >= z_low && z_new <= z_up {
+                z_new
+            } else {
+                0.5 * (z_low + z_up)
+            };
+            delta_z = z_new - z;
+            z = z_new;
+            iterations += 1;
+        }
+
+        let last_step = delta_z.abs();
+        let residual = time(z) - tof;
+        if last_step.is_nan() || last_step > options.tolerance {
+            return Err(LambertError::NotConverged {
+                iterations,
+                last_step,
+                residual,
+            });
+        }
+
+        let y_z = y(z);
+        if opposite {
+            return Ok(LambertSolution {
+                iterations,
+                last_step,
+                residual,
+                warning: plane.warning,
+                ..opposite_velocities(r1_v, r2_v, plane.i_h, z, y_z, tof, mu)
+            });
+        }
+
+        // Lagrange coefficients
+        let f = 1.0 - y_z / r1;
+        let g = a * (y_z / mu).sqrt();
+        let g_dot = 1.0 - y_z / r2;
+
+        Ok(LambertSolution {
+            v1: (r2_v - f * r1_v) / g,
+            v2: (g_dot * r2_v - r1_v) / g,
+            revolutions: 0,
+            branch: None,
+            iterations,
+            last_step,
+            residual,
+            warning: plane.warning,
+        })
+    }
+}
+
+fn opposite_velocities(
+    r1_v: Vector3D,
+    r2_v: Vector3D,
+    i_h: Vector3D,
+    z: f64,
+    y: f64,
+    tof: f64,
+    mu: f64,
+) -> LambertSolution {
+    // 180 degree transfer: 1/r1 + 1/r2 = 2/p fixes the transverse velocities, the
+    // radial velocity at r1 follows from the universal Kepler equation and is
+    // reversed at r2, half an orbit later in true anomaly
+    let (r1, r2) = (r1_v.mag(), r2_v.mag());
+    let (c, s) = (stumpff_c(z), stumpff_s(z));
+    let x = (y / c).sqrt();
+    let sigma = (mu.sqrt() * tof - x.powf(3.0) * s - r1 * x * (1.0 - z * s)) / (x.powf(2.0) * c);
+    let v_r = sigma * mu.sqrt() / r1;
+    let h = (mu * 2.0 * r1 * r2 / (r1 + r2)).sqrt();
+    let velocity = |r_v: Vector3D, v_r: f64| {
+        let i_r = r_v.norm();
+        v_r * i_r + h / r_v.mag() * i_h.cross(i_r)
+    };
+    LambertSolution {
+        v1: velocity(r1_v, v_r),
+        v2: velocity(r2_v, -v_r),
+        revolutions: 0,
+        branch: None,
+        iterations: 0,
+        last_step: 0.0,
+        residual: 0.0,
+        warning: None,
+    }
+}
+
+fn stumpff_c(z: f64) -> f64 {
+    if z.abs() < SERIES_RANGE {
+        1.0 / 2.0 - z / 24.0 + z.powf(2.0) / 720.0 - z.powf(3.0) / 40320.0
+    } else if z > 0.0 {
+        (1.0 - z.sqrt().cos()) / z
+    } else {
+        ((-z).sqrt().cosh() - 1.0) / -z
+    }
+}
+
+fn stumpff_s(z: f64) -> f64 {
+    if z.abs() < SERIES_RANGE {
+        1.0 / 6.0 - z / 120.0 + z.powf(2.0) / 5040.0 - z.powf(3.0) / 362880.0
+    } else if z > 0.0 {
+        (z.sqrt() - z.sqrt().sin()) / z.powf(1.5)
+    } else {
+        ((-z).sqrt().sinh() - (-z).sqrt()) / (-z).powf(1.5)
+    }
+}
+
+fn time_derivative(z: f64, y: f64, a: f64) -> f64 {
+    // derivative of sqrt(mu) * time-of-flight by z
+    let c = stumpff_c(z);
+    let s = stumpff_s(z);
+    if z.abs() < SERIES_RANGE {
+        2f64.sqrt() / 40.0 * y.powf(1.5) + a / 8.0 * (y.sqrt() + a * (1.0 / (2.0 * y)).sqrt())
+    } else {
+        (y / c).powf(1.5)
+            * (1.0 / (2.0 * z) * (c - 3.0 * s / (2.0 * c)) + 3.0 * s.powf(2.0) / (4.0 * c))
+            + a / 8.0 * (3.0 * s * y.sqrt() / c + a * (c / y).sqrt())
+    }
+}
@@ -1,7 +1,23 @@
+#[cfg(feature = "serde")]
+pub mod batch;
+pub mod bodies;
+mod csv;
+#[cfg(feature = "gui")]
 pub mod data;
+#[cfg(feature = "gui")]
+pub mod delegate;
+pub mod ephemeris;
+pub mod kepler;
 pub mod lambert;
+pub mod maneuvers;
 pub mod orbit;
+pub mod porkchop;
+pub mod render;
+#[cfg(feature = "serde")]
+pub mod scenario;
+pub mod time;
 pub mod vectors;
+#[cfg(feature = "gui")]
 pub mod view;
 
 pub use lambert::LambertSolver;
@@ -1,14 +1,19 @@
 use druid::{AppLauncher, WindowDesc};
 
 use orbital::data::AppState;
-use orbital::view::build_ui;
+use orbital::delegate::Delegate;
+use orbital::view::{build_menu, build_ui};
 
 fn main() {
-    let main_window = WindowDesc::new(build_ui).title("Lambert solver");
+    let mut initial_state = AppState::initial_earth();
+    initial_state.load_recent_files();
 
-    let initial_state = AppState::initial_earth().lambert_problem;
+    let main_window = WindowDesc::new(build_ui)
+        .title("Lambert solver")
+        .menu(build_menu(&initial_state));
 
     AppLauncher::with_window(main_window)
+        .delegate(Delegate::default())
         .launch(initial_state)
         .expect("Failed to launch application");
 }
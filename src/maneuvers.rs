@@ -0,0 +1,648 @@
+// Analytic impulsive maneuvers: coplanar Hohmann transfers with two tangential burns
+// 180 degrees apart and bi-elliptic transfers with a third burn at an intermediate
+// apoapsis between circular or coaxial elliptic orbits, plane changes at a node and
+// Hohmann transfers between inclined circular orbits
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::bodies::Body;
+use crate::{Orbit, Vector3D};
+
+// eccentricity below which an orbit has no line of apsides
+const CIRCULAR_TOLERANCE: f64 = 1e-9;
+// largest angle (radians) between the orbit normals or lines of apsides
+const ANGLE_TOLERANCE: f64 = 1e-6;
+
+// Side of the line of apsides where the first burn takes place: the periapsis or
+// apoapsis of the initial orbit, or of the target orbit if the initial one is
+// circular. Between two circular orbits the transfer starts at the initial position.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Apse {
+    #[default]
+    Periapsis,
+    Apoapsis,
+}
+
+// Node of the orbit on the equator (XY plane), or for transfers between inclined
+// orbits where the initial orbit rises through the target plane. Equatorial
+// orbits use the X axis as the line of nodes.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Node {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManeuverError {
+    // non-positive or non-finite input parameter, or an open orbit
+    InvalidInput { parameter: &'static str, value: f64 },
+    // orbits around bodies with different gravitational parameters
+    MismatchedMu { initial: f64, target: f64 },
+    // angle (radians) between the orbit normals, including opposite directions of motion
+    NotCoplanar { angle: f64 },
+    // angle (radians) between the lines of apsides of elliptic orbits
+    NotCoaxial { angle: f64 },
+}
+
+impl fmt::Display for ManeuverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidInput { parameter, value } => {
+                write!(f, "invalid input, {} = {}", parameter, value)
+            }
+            Self::MismatchedMu { initial, target } => write!(
+                f,
+                "orbits have different gravitational parameters {} and {}",
+                initial, target
+            ),
+            Self::NotCoplanar { angle } => write!(
+                f,
+                "orbits are not coplanar, normals differ by {:.6} deg",
+                angle.to_degrees()
+            ),
+            Self::NotCoaxial { angle } => write!(
+                f,
+                "orbits are not coaxial, lines of apsides differ by {:.6} deg",
+                angle.to_degrees()
+            ),
+        }
+    }
+}
+
+impl Error for ManeuverError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Burn {
+    // time after the first burn (s)
+    pub time: f64,
+    pub position: Vector3D,
+    // impulsive velocity change (km/s), along the velocity at the apsides
+    pub delta_v: Vector3D,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+    pub burns: Vec<Burn>,
+    // transfer orbits between consecutive burns, at the start of each coast
+    pub orbits: Vec<Orbit>,
+}
+
+// Single burn at a node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Maneuver {
+    // burn time measured from the current position on the initial orbit
+    pub burn: Burn,
+    // orbit after the burn, at the node
+    pub orbit: Orbit,
+}
+
+impl Transfer {
+    pub fn delta_v(&self) -> f64 {
+        // sum of the burn magnitudes
+        self.burns.iter().map(|burn| burn.delta_v.mag()).sum()
+    }
+
+    pub fn time_of_flight(&self) -> f64 {
+        self.burns.last().map_or(0.0, |burn| burn.time)
+    }
+}
+
+pub fn circular_orbit(body: &Body, radius: f64) -> Orbit {
+    // equatorial and prograde, starting on the X axis
+    Orbit::new(radius, 0.0, 0.0, 0.0, 0.0, 0.0, body.mu)
+}
+
+pub fn hohmann(
+    initial: &Orbit,
+    target: &Orbit,
+    departure: Apse,
+) -> Result<Transfer, ManeuverError> {
+    let (direction, normal) = transfer_axis(initial, target, departure)?;
+    let r1 = radius_towards(initial, direction);
+    let r2 = radius_towards(target, -direction);
+    let mut transfer = Transfer {
+        burns: Vec::new(),
+        orbits: Vec::new(),
+    };
+    transfer.coast(initial, r1 * direction, r2, normal);
+    transfer.arrive(target);
+    Ok(transfer)
+}
+
+pub fn bi_elliptic(
+    initial: &Orbit,
+    target: &Orbit,
+    r_b: f64,
+    departure: Apse,
+) -> Result<Transfer, ManeuverError> {
+    // out to radius r_b opposite the departure point and back to the target on the
+    // departure side
+    let (direction, normal) = transfer_axis(initial, target, departure)?;
+    let r1 = radius_towards(initial, direction);
+    let r2 = radius_towards(target, direction);
+    if !r_b.is_finite() || r_b < r1.max(r2) {
+        return Err(ManeuverError::InvalidInput {
+            parameter: "r_b",
+            value: r_b,
+        });
+    }
+    let mut transfer = Transfer {
+        burns: Vec::new(),
+        orbits: Vec::new(),
+    };
+    transfer.coast(initial, r1 * direction, r_b, normal);
+    let first = transfer.orbits[0];
+    transfer.coast(&first, -r_b * direction, r2, normal);
+    transfer.arrive(target);
+    Ok(transfer)
+}
+
+pub fn plane_change(orbit: &Orbit, inc: f64, node: Node) -> Result<Maneuver, ManeuverError> {
+    // rotates the velocity about the line of nodes to inclination inc, keeping the
+    // shape of the orbit and the node
+    check_orbit("orbit", orbit)?;
+    check_inclination(inc)?;
+    let (r, v) = state_at_node(orbit, node);
+    let v_new = rotate(v, node_direction(orbit), inc - orbit.inc);
+    Ok(burn_at(orbit, r, v_new))
+}
+
+pub fn combined_plane_change(
+    orbit: &Orbit,
+    inc: f64,
+    r_opposite: f64,
+    node: Node,
+) -> Result<Maneuver, ManeuverError> {
+    // one burn at the node to inclination inc onto the orbit with its apses at the
+    // node and at radius r_opposite on the other side
+    check_orbit("orbit", orbit)?;
+    check_inclination(inc)?;
+    if !r_opposite.is_finite() || r_opposite <= 0.0 {
+        return Err(ManeuverError::InvalidInput {
+            parameter: "r_opposite",
+            value: r_opposite,
+        });
+    }
+    let (r, v) = state_at_node(orbit, node);
+    let normal = rotate(r.cross(v).norm(), node_direction(orbit), inc - orbit.inc);
+    let a = (r.mag() + r_opposite) / 2.0;
+    let v_new = (orbit.mu * (2.0 / r.mag() - 1.0 / a)).sqrt() * normal.cross(r).norm();
+    Ok(burn_at(orbit, r, v_new))
+}
+
+pub fn hohmann_plane_change(
+    initial: &Orbit,
+    target: &Orbit,
+    departure: Node,
+) -> Result<(Transfer, f64), ManeuverError> {
+    // Hohmann transfer between inclined circular orbits with burns on their line of
+    // intersection. The plane change is split between the burns to minimise the
+    // total delta-v, the second value is the part (radians) at the first burn.
+    check_orbits(initial, target)?;
+    for (parameter, orbit) in &[
+        ("initial eccentricity", initial),
+        ("target eccentricity", target),
+    ] {
+        if orbit.ecc >= CIRCULAR_TOLERANCE {
+            return Err(ManeuverError::InvalidInput {
+                parameter,
+                value: orbit.ecc,
+            });
+        }
+    }
+    let (normal, target_normal) = (orbit_normal(initial), orbit_normal(target));
+    let delta_i = angle_between(normal, target_normal);
+    let line = target_normal.cross(normal);
+    if line.mag() < ANGLE_TOLERANCE {
+        if delta_i > PI / 2.0 {
+            return Err(ManeuverError::NotCoplanar { angle: delta_i });
+        }
+        return Ok((hohmann(initial, target, Apse::Periapsis)?, 0.0));
+    }
+    let axis = line.norm();
+    let direction = match departure {
+        Node::Ascending => axis,
+        Node::Descending => -axis,
+    };
+
+    // speeds on the circles and at the apses of the transfer ellipse
+    let mu = initial.mu;
+    let (r1, r2) = (initial.p, target.p);
+    let a = (r1 + r2) / 2.0;
+    let (v1, v2) = ((mu / r1).sqrt(), (mu / r2).sqrt());
+    let v_departure = (mu * (2.0 / r1 - 1.0 / a)).sqrt();
+    let v_arrival = (mu * (2.0 / r2 - 1.0 / a)).sqrt();
+    let burn = |v: f64, w: f64, angle: f64| (v * v + w * w - 2.0 * v * w * angle.cos()).sqrt();
+    let cost = |split: f64| burn(v1, v_departure, split) + burn(v_arrival, v2, delta_i - split);
+    let split = golden_section_minimum(cost, 0.0, delta_i);
+
+    let mut transfer = Transfer {
+        burns: Vec::new(),
+        orbits: Vec::new(),
+    };
+    transfer.coast(initial, r1 * direction, r2, rotate(normal, -axis, split));
+    transfer.arrive(target);
+    Ok((transfer, split))
+}
+
+impl Transfer {
+    fn coast(&mut self, from: &Orbit, r: Vector3D, r_opposite: f64, normal: Vector3D) {
+        // burn from `from` at r onto the ellipse with its other apse at r_opposite
+        // and coast half an orbit
+        let mu = from.mu;
+        let a = (r.mag() + r_opposite) / 2.0;
+        let v = (mu * (2.0 / r.mag() - 1.0 / a)).sqrt() * normal.cross(r).norm();
+        let orbit = Orbit::from_rv(r, v, mu);
+        self.burns.push(Burn {
+            time: self.next_burn_time(),
+            position: r,
+            delta_v: v - velocity_at(from, r),
+        });
+        self.orbits.push(orbit);
+    }
+
+    fn arrive(&mut self, target: &Orbit) {
+        // burn onto the target at the end of the last coast
+        let orbit = self.orbits[self.orbits.len() - 1];
+        let r = orbit.position_at(orbit.nu + PI);
+        self.burns.push(Burn {
+            time: self.next_burn_time(),
+            position: r,
+            delta_v: velocity_at(target, r) - velocity_at(&orbit, r),
+        });
+    }
+
+    fn next_burn_time(&self) -> f64 {
+        // half an orbit after the last burn
+        match self.orbits.last() {
+            Some(orbit) => self.time_of_flight() + orbit.period() / 2.0,
+            None => 0.0,
+        }
+    }
+}
+
+fn transfer_axis(
+    initial: &Orbit,
+    target: &Orbit,
+    departure: Apse,
+) -> Result<(Vector3D, Vector3D), ManeuverError> {
+    // unit vector towards the first burn and the common orbit normal
+    check_orbits(initial, target)?;
+    let (p1, p2) = (initial.perifocal_basis().0, target.perifocal_basis().0);
+    let (normal, target_normal) = (orbit_normal(initial), orbit_normal(target));
+    let angle = angle_between(normal, target_normal);
+    if angle > ANGLE_TOLERANCE {
+        return Err(ManeuverError::NotCoplanar { angle });
+    }
+
+    let periapsis = match (
+        initial.ecc < CIRCULAR_TOLERANCE,
+        target.ecc < CIRCULAR_TOLERANCE,
+    ) {
+        (false, false) => {
+            // opposite periapses share the line of apsides
+            let angle = angle_between(p1, p2).min(angle_between(p1, -p2));
+            if angle > ANGLE_TOLERANCE {
+                return Err(ManeuverError::NotCoaxial { angle });
+            }
+            p1
+        }
+        (false, true) => p1,
+        (true, false) => p2,
+        (true, true) => return Ok((initial.to_rv(initial.mu).0.norm(), normal)),
+    };
+    match departure {
+        Apse::Periapsis => Ok((periapsis, normal)),
+        Apse::Apoapsis => Ok((-periapsis, normal)),
+    }
+}
+
+fn check_orbits(initial: &Orbit, target: &Orbit) -> Result<(), ManeuverError> {
+    check_orbit("initial orbit", initial)?;
+    check_orbit("target orbit", target)?;
+    if (initial.mu - target.mu).abs() > 1e-12 * initial.mu {
+        return Err(ManeuverError::MismatchedMu {
+            initial: initial.mu,
+            target: target.mu,
+        });
+    }
+    Ok(())
+}
+
+fn check_orbit(parameter: &'static str, orbit: &Orbit) -> Result<(), ManeuverError> {
+    // closed orbits only, reported by their eccentricity
+    let valid = orbit.mu > 0.0 && orbit.p > 0.0 && orbit.p.is_finite();
+    if !valid || !(0.0..1.0).contains(&orbit.ecc) {
+        return Err(ManeuverError::InvalidInput {
+            parameter,
+            value: orbit.ecc,
+        });
+    }
+    Ok(())
+}
+
+fn check_inclination(inc: f64) -> Result<(), ManeuverError> {
+    if !(0.0..=PI).contains(&inc) {
+        return Err(ManeuverError::InvalidInput {
+            parameter: "inclination",
+            value: inc,
+        });
+    }
+    Ok(())
+}
+
+fn burn_at(orbit: &Orbit, r: Vector3D, v: Vector3D) -> Maneuver {
+    // impulse from the orbit onto velocity v at r, coasting there from the current position
+    let nu = true_anomaly_towards(orbit, r);
+    Maneuver {
+        burn: Burn {
+            time: orbit.time_of_flight(orbit.nu, nu),
+            position: r,
+            delta_v: v - velocity_at(orbit, r),
+        },
+        orbit: Orbit::from_rv(r, v, orbit.mu),
+    }
+}
+
+fn node_direction(orbit: &Orbit) -> Vector3D {
+    // towards the ascending node
+    Vector3D::new(orbit.lan.cos(), orbit.lan.sin(), 0.0)
+}
+
+fn state_at_node(orbit: &Orbit, node: Node) -> (Vector3D, Vector3D) {
+    let direction = match node {
+        Node::Ascending => node_direction(orbit),
+        Node::Descending => -node_direction(orbit),
+    };
+    let nu = true_anomaly_towards(orbit, direction);
+    Orbit { nu, ..*orbit }.to_rv(orbit.mu)
+}
+
+fn orbit_normal(orbit: &Orbit) -> Vector3D {
+    let (i_p, i_q) = orbit.perifocal_basis();
+    i_p.cross(i_q)
+}
+
+fn rotate(v: Vector3D, axis: Vector3D, angle: f64) -> Vector3D {
+    // right-handed rotation about a unit axis (Rodrigues' formula)
+    let (sin, cos) = angle.sin_cos();
+    cos * v + sin * axis.cross(v) + (1.0 - cos) * (axis * v) * axis
+}
+
+fn golden_section_minimum(f: impl Fn(f64) -> f64, mut a: f64, mut b: f64) -> f64 {
+    // minimum of a unimodal function on [a, b]
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    while b - a > 1e-12 {
+        let (c, d) = (b - ratio * (b - a), a + ratio * (b - a));
+        if f(c) < f(d) {
+            b = d;
+        } else {
+            a = c;
+        }
+    }
+    (a + b) / 2.0
+}
+
+fn angle_between(a: Vector3D, b: Vector3D) -> f64 {
+    a.cross(b).mag().atan2(a * b)
+}
+
+fn true_anomaly_towards(orbit: &Orbit, direction: Vector3D) -> f64 {
+    let (i_p, i_q) = orbit.perifocal_basis();
+    (direction * i_q).atan2(direction * i_p)
+}
+
+fn radius_towards(orbit: &Orbit, direction: Vector3D) -> f64 {
+    orbit.radius_at(true_anomaly_towards(orbit, direction))
+}
+
+fn velocity_at(orbit: &Orbit, r: Vector3D) -> Vector3D {
+    // velocity where the orbit crosses the direction of r
+    let nu = true_anomaly_towards(orbit, r);
+    Orbit { nu, ..*orbit }.to_rv(orbit.mu).1
+}
+
+#[cfg(test)]
+#[test]
+fn test_hohmann() {
+    // LEO at 300 km to GEO: 2.426 + 1.467 km/s in 5.27 hours
+    let leo = circular_orbit(&crate::bodies::EARTH, 6678.0);
+    let geo = circular_orbit(&crate::bodies::EARTH, 42164.0);
+    let transfer = hohmann(&leo, &geo, Apse::Periapsis).unwrap();
+    assert_eq!(transfer.burns.len(), 2);
+    assert_eq!(transfer.orbits.len(), 1);
+    assert!((transfer.burns[0].delta_v.mag() - 2.426).abs() < 1e-3);
+    assert!((transfer.burns[1].delta_v.mag() - 1.467).abs() < 1e-3);
+    assert!((transfer.delta_v() - 3.893).abs() < 1e-3);
+    assert!((transfer.time_of_flight() / 3600.0 - 5.275).abs() < 1e-2);
+    assert!((transfer.burns[1].position - Vector3D::new(-42164.0, 0.0, 0.0)).mag() < 1e-6);
+
+    let orbit = transfer.orbits[0];
+    assert!((orbit.periapsis_radius() - 6678.0).abs() < 1e-6);
+    assert!((orbit.apoapsis_radius() - 42164.0).abs() < 1e-6);
+
+    // lowering the orbit reverses the burns
+    let back = hohmann(&geo, &leo, Apse::Periapsis).unwrap();
+    assert!((back.delta_v() - transfer.delta_v()).abs() < 1e-9);
+    assert!(back.burns[0].delta_v * Vector3D::new(0.0, 1.0, 0.0) < 0.0);
+}
+
+#[test]
+fn test_coaxial_ellipses() {
+    // circularizing at apoapsis is a single burn
+    let mu = crate::bodies::EARTH.mu;
+    let ellipse = Orbit::new(10500.0, 1.0 / 3.0, 0.3, 0.2, 0.1, 0.0, mu);
+    let circle = Orbit::new(14000.0, 0.0, 0.3, 0.2, 0.0, 0.0, mu);
+    let transfer = hohmann(&ellipse, &circle, Apse::Apoapsis).unwrap();
+    let v_apoapsis = (mu * (2.0 / 14000.0 - 1.0 / 10500.0)).sqrt();
+    assert!((transfer.burns[0].delta_v.mag() - ((mu / 14000.0).sqrt() - v_apoapsis)).abs() < 1e-9);
+    assert!(transfer.burns[1].delta_v.mag() < 1e-9);
+
+    // between ellipses with opposite periapses, from periapsis to periapsis
+    let target = Orbit::new(20000.0, 0.5, 0.3, 0.2, 0.1 + PI, 0.0, mu);
+    let transfer = hohmann(&ellipse, &target, Apse::Periapsis).unwrap();
+    assert!((transfer.orbits[0].apoapsis_radius() - 10000.0).abs() < 1e-6);
+    assert!((transfer.burns[1].position.mag() - 10000.0).abs() < 1e-6);
+
+    let rotated = Orbit::new(20000.0, 0.5, 0.3, 0.2, 0.5, 0.0, mu);
+    assert!(matches!(
+        hohmann(&ellipse, &rotated, Apse::Periapsis),
+        Err(ManeuverError::NotCoaxial { .. })
+    ));
+    let inclined = Orbit::new(14000.0, 0.0, 0.4, 0.2, 0.0, 0.0, mu);
+    assert!(matches!(
+        hohmann(&ellipse, &inclined, Apse::Periapsis),
+        Err(ManeuverError::NotCoplanar { .. })
+    ));
+    let retrograde = Orbit::new(14000.0, 0.0, PI - 0.3, 0.2 + PI, 0.0, 0.0, mu);
+    assert!(matches!(
+        hohmann(&ellipse, &retrograde, Apse::Periapsis),
+        Err(ManeuverError::NotCoplanar { .. })
+    ));
+    let hyperbola = Orbit::new(-20000.0, 1.5, 0.3, 0.2, 0.1, 0.0, mu);
+    assert!(matches!(
+        hohmann(&ellipse, &hyperbola, Apse::Periapsis),
+        Err(ManeuverError::InvalidInput { .. })
+    ));
+    let moon = Orbit::new(14000.0, 0.0, 0.3, 0.2, 0.0, 0.0, 4902.8);
+    assert!(matches!(
+        hohmann(&ellipse, &moon, Apse::Periapsis),
+        Err(ManeuverError::MismatchedMu { .. })
+    ));
+}
+
+#[test]
+fn test_bi_elliptic() {
+    // cheaper than Hohmann for radius ratios above about 15.6 with a far apoapsis
+    let initial = circular_orbit(&crate::bodies::EARTH, 7000.0);
+    let target = circular_orbit(&crate::bodies::EARTH, 7000.0 * 20.0);
+    let transfer = bi_elliptic(&initial, &target, 7000.0 * 60.0, Apse::Periapsis).unwrap();
+    assert_eq!(transfer.burns.len(), 3);
+    assert_eq!(transfer.orbits.len(), 2);
+    assert!(
+        transfer.delta_v()
+            < hohmann(&initial, &target, Apse::Periapsis)
+                .unwrap()
+                .delta_v()
+    );
+    assert!((transfer.burns[1].position - Vector3D::new(-420000.0, 0.0, 0.0)).mag() < 1e-6);
+    assert!((transfer.burns[2].position - Vector3D::new(140000.0, 0.0, 0.0)).mag() < 1e-6);
+    let half_periods: f64 = transfer.orbits.iter().map(|o| o.period() / 2.0).sum();
+    assert!((transfer.time_of_flight() - half_periods).abs() < 1e-6);
+
+    // with the intermediate apoapsis at the target radius it is a Hohmann transfer
+    let hohmann = hohmann(&initial, &target, Apse::Periapsis).unwrap();
+    let degenerate = bi_elliptic(&initial, &target, 140000.0, Apse::Periapsis).unwrap();
+    assert!((degenerate.delta_v() - hohmann.delta_v()).abs() < 1e-9);
+    assert!(bi_elliptic(&initial, &target, 100000.0, Apse::Periapsis).is_err());
+}
+
+#[test]
+fn test_lambert_cross_check() {
+    // the Lambert solution on the same 180 degree geometry needs the same burns
+    let mu = crate::bodies::EARTH.mu;
+    let initial = Orbit::new(9000.0, 0.2, 0.5, 1.0, 2.0, 0.0, mu);
+    let target = Orbit::new(30000.0, 0.1, 0.5, 1.0, 2.0 + PI, 0.0, mu);
+    for &departure in &[Apse::Periapsis, Apse::Apoapsis] {
+        let transfer = hohmann(&initial, &target, departure).unwrap();
+        let (first, last) = (transfer.burns[0], transfer.burns[1]);
+        let (i_p, i_q) = initial.perifocal_basis();
+        let options = crate::lambert::LambertOptions {
+            normal: Some(i_p.cross(i_q)),
+            ..Default::default()
+        };
+        let mut ls = crate::LambertSolver::with_options(
+            first.position,
+            last.position,
+            transfer.time_of_flight(),
+            mu,
+            options,
+        )
+        .unwrap();
+        ls.v_dep = velocity_at(&initial, first.position);
+        ls.v_arr = velocity_at(&target, last.position);
+        let dv = ls.get_delta_v().unwrap();
+        assert!((dv.departure - first.delta_v).mag() < 1e-6);
+        assert!((dv.arrival - last.delta_v).mag() < 1e-6);
+    }
+}
+
+#[test]
+fn test_plane_change() {
+    // 2 v sin(di / 2) on a circular orbit, the radial velocity is kept on an ellipse
+    let mu = crate::bodies::EARTH.mu;
+    let leo = Orbit::new(6678.0, 0.0, 0.5, 1.0, 0.0, 2.0, mu);
+    let maneuver = plane_change(&leo, 0.3, Node::Descending).unwrap();
+    let v = (mu / 6678.0).sqrt();
+    assert!((maneuver.burn.delta_v.mag() - 2.0 * v * 0.1f64.sin()).abs() < 1e-9);
+    assert!((maneuver.orbit.inc - 0.3).abs() < 1e-9);
+    assert!((maneuver.orbit.lan - 1.0).abs() < 1e-9);
+    assert!((maneuver.orbit.a - 6678.0).abs() < 1e-6);
+    let descending = Vector3D::new(-1.0f64.cos(), -1.0f64.sin(), 0.0);
+    assert!((maneuver.burn.position - 6678.0 * descending).mag() < 1e-6);
+    let to_node = (PI - 2.0).rem_euclid(2.0 * PI) / (mu / 6678.0f64.powf(3.0)).sqrt();
+    assert!((maneuver.burn.time - to_node).abs() < 1e-6);
+
+    let ellipse = Orbit::new(10000.0, 0.3, 0.2, 0.5, 1.0, 0.0, mu);
+    let maneuver = plane_change(&ellipse, 0.6, Node::Ascending).unwrap();
+    let (r, v) = (
+        maneuver.burn.position,
+        velocity_at(&ellipse, maneuver.burn.position),
+    );
+    assert!(maneuver.burn.delta_v * r.norm() < 1e-12);
+    let v_t = (v - (v * r.norm()) * r.norm()).mag();
+    assert!((maneuver.burn.delta_v.mag() - 2.0 * v_t * 0.2f64.sin()).abs() < 1e-9);
+    assert!((maneuver.orbit.ecc - 0.3).abs() < 1e-9 && (maneuver.orbit.a - 10000.0).abs() < 1e-6);
+    assert!((maneuver.orbit.inc - 0.6).abs() < 1e-9);
+
+    // equatorial orbits tilt about the X axis, retrograde ones too
+    let equatorial = Orbit::new(7000.0, 0.0, PI, 0.0, 0.0, 0.0, mu);
+    let maneuver = plane_change(&equatorial, PI - 0.1, Node::Ascending).unwrap();
+    assert!((maneuver.orbit.inc - (PI - 0.1)).abs() < 1e-9);
+    assert!(maneuver.orbit.lan.abs() < 1e-9);
+    assert!(plane_change(&leo, -0.1, Node::Ascending).is_err());
+}
+
+#[test]
+fn test_combined_plane_change() {
+    // law of cosines between the circular and the new periapsis velocity
+    let mu = crate::bodies::EARTH.mu;
+    let leo = Orbit::new(6678.0, 0.0, 0.5, 0.0, 0.0, 0.0, mu);
+    let maneuver = combined_plane_change(&leo, 0.2, 42164.0, Node::Ascending).unwrap();
+    let a = (6678.0 + 42164.0) / 2.0;
+    let (v, v_p) = ((mu / 6678.0).sqrt(), (mu * (2.0 / 6678.0 - 1.0 / a)).sqrt());
+    let expected = (v * v + v_p * v_p - 2.0 * v * v_p * 0.3f64.cos()).sqrt();
+    assert!((maneuver.burn.delta_v.mag() - expected).abs() < 1e-9);
+    assert!((maneuver.orbit.inc - 0.2).abs() < 1e-9);
+    assert!((maneuver.orbit.periapsis_radius() - 6678.0).abs() < 1e-6);
+    assert!((maneuver.orbit.apoapsis_radius() - 42164.0).abs() < 1e-6);
+
+    // without a plane change it is the first Hohmann burn
+    let geo = Orbit::new(42164.0, 0.0, 0.5, 0.0, 0.0, 0.0, mu);
+    let maneuver = combined_plane_change(&leo, 0.5, 42164.0, Node::Ascending).unwrap();
+    let transfer = hohmann(&leo, &geo, Apse::Periapsis).unwrap();
+    assert!((maneuver.burn.delta_v - transfer.burns[0].delta_v).mag() < 1e-9);
+    assert!(combined_plane_change(&leo, 0.2, 0.0, Node::Ascending).is_err());
+}
+
+#[test]
+fn test_hohmann_plane_change() {
+    // LEO at 28.5 degrees to GEO: about 2 degrees of the plane change at the first
+    // burn saves some 30 m/s over doing it all at apoapsis
+    let mu = crate::bodies::EARTH.mu;
+    let leo = Orbit::new(6678.0, 0.0, 28.5f64.to_radians(), 0.3, 0.0, 0.0, mu);
+    let geo = circular_orbit(&crate::bodies::EARTH, 42164.0);
+    let (transfer, split) = hohmann_plane_change(&leo, &geo, Node::Descending).unwrap();
+    assert!(
+        split > 1f64.to_radians() && split < 3f64.to_radians(),
+        "{}",
+        split
+    );
+    assert!((transfer.orbits[0].inc - (28.5f64.to_radians() - split)).abs() < 1e-9);
+    let a = (6678.0 + 42164.0) / 2.0;
+    let v_p = (mu * (2.0 / 6678.0 - 1.0 / a)).sqrt();
+    let v_a = (mu * (2.0 / 42164.0 - 1.0 / a)).sqrt();
+    let (v1, v2) = ((mu / 6678.0).sqrt(), (mu / 42164.0).sqrt());
+    let all_at_apoapsis =
+        v_p - v1 + (v_a * v_a + v2 * v2 - 2.0 * v_a * v2 * 28.5f64.to_radians().cos()).sqrt();
+    let saving = all_at_apoapsis - transfer.delta_v();
+    assert!(saving > 0.01 && saving < 0.05, "{}", saving);
+
+    // burns on the line of nodes, ending on the target orbit
+    let line = Vector3D::new(-0.3f64.cos(), -0.3f64.sin(), 0.0);
+    assert!((transfer.burns[0].position - 6678.0 * line).mag() < 1e-6);
+    assert!((transfer.burns[1].position + 42164.0 * line).mag() < 1e-6);
+    let arrival = transfer.burns[1];
+    let v_final = velocity_at(&transfer.orbits[0], arrival.position) + arrival.delta_v;
+    assert!((v_final - velocity_at(&geo, arrival.position)).mag() < 1e-9);
+
+    // coplanar orbits reduce to the Hohmann transfer
+    let low = circular_orbit(&crate::bodies::EARTH, 6678.0);
+    let (coplanar, split) = hohmann_plane_change(&low, &geo, Node::Ascending).unwrap();
+    assert_eq!(split, 0.0);
+    assert_eq!(coplanar, hohmann(&low, &geo, Apse::Periapsis).unwrap());
+    let ellipse = Orbit::new(10000.0, 0.1, 0.5, 0.0, 0.0, 0.0, mu);
+    assert!(hohmann_plane_change(&ellipse, &geo, Node::Ascending).is_err());
+}
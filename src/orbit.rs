@@ -1,14 +1,53 @@
 use std::f64::consts::PI;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::kepler::{self, Conic};
 use crate::Vector3D;
 
-#[derive(Default, Debug)]
+// eccentricity and inclination (or its distance from 180 degrees) below which
+// the orbit is treated as circular or equatorial
+const SINGULAR_TOLERANCE: f64 = 1e-11;
+
+// Meaning of the angular elements. Undefined angles of circular and equatorial
+// orbits are set to zero and the remaining ones measured from the node or X axis.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AngleConvention {
+    #[default]
+    Classical,
+    // circular inclined: argp = 0, nu is the argument of latitude
+    ArgumentOfLatitude,
+    // equatorial elliptic: lan = 0, argp is the longitude of periapsis
+    LongitudeOfPeriapsis,
+    // circular equatorial: lan = argp = 0, nu is the true longitude
+    TrueLongitude,
+}
+
+impl AngleConvention {
+    pub fn for_orbit(ecc: f64, inc: f64) -> Self {
+        let circular = ecc < SINGULAR_TOLERANCE;
+        let equatorial = inc < SINGULAR_TOLERANCE || PI - inc < SINGULAR_TOLERANCE;
+        match (circular, equatorial) {
+            (false, false) => Self::Classical,
+            (true, false) => Self::ArgumentOfLatitude,
+            (false, true) => Self::LongitudeOfPeriapsis,
+            (true, true) => Self::TrueLongitude,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Orbit {
     // eccentricity
     pub ecc: f64,
-    // semi-major axis
+    // semi-major axis, infinite for parabolic orbits
     pub a: f64,
-    // inclination
+    // semi-latus rectum
+    pub p: f64,
+    // inclination (radians)
     pub inc: f64,
     // LAN
     pub lan: f64,
@@ -16,14 +55,34 @@ pub struct Orbit {
     pub argp: f64,
     // true anomaly
     pub nu: f64,
+    // gravitational parameter of the central body
+    pub mu: f64,
+    pub convention: AngleConvention,
 }
 
 impl Orbit {
+    pub fn new(a: f64, ecc: f64, inc: f64, lan: f64, argp: f64, nu: f64, mu: f64) -> Self {
+        // orbit from classical elements, angles in radians. Not valid for parabolic
+        // orbits, which need the semi-latus rectum set directly.
+        Self {
+            ecc,
+            a,
+            p: a * (1.0 - ecc.powf(2.0)),
+            inc,
+            lan,
+            argp,
+            nu,
+            mu,
+            convention: AngleConvention::for_orbit(ecc, inc),
+        }
+    }
+
     pub fn from_rv(r: Vector3D, v: Vector3D, mu: f64) -> Self {
         let h = r.cross(v);
+        let i_h = h.norm();
         let ecc_v = v.cross(h) / mu - r.norm();
         let n_v = Vector3D::new(-h.y, h.x, 0.0);
-        let inc = h.z / h.mag();
+        let inc = n_v.mag().atan2(h.z);
 
         // calculate semi-latus rectum
         let p = h * h / mu;
@@ -34,44 +93,465 @@ impl Orbit {
         // calculate semi-major axis
         let a = p / (1.0 - ecc_v.mag().powf(2.0));
 
-        // calculate true anomaly
-        let nu = if ecc_v.mag() != 0.0 {
-            let tmp = (ecc_v * r / r.mag() / ecc_v.mag()).acos();
-            if r * v >= 0.0 {
-                tmp
-            } else {
-                2.0 * PI - tmp
-            }
-        } else if inc != 0.0 {
-            let tmp = (n_v * r / r.mag() / n_v.mag()).acos();
-            if r.z >= 0.0 {
-                tmp
-            } else {
-                2.0 * PI - tmp
-            }
-        } else {
-            let tmp = (r.x / r.mag()).acos();
-            if v.x <= 0.0 {
-                tmp
-            } else {
-                2.0 * PI - tmp
-            }
+        // calculate lan, argp and true anomaly, substituting undefined angles
+        let convention = AngleConvention::for_orbit(ecc_v.mag(), inc);
+        let i_x = Vector3D::new(1.0, 0.0, 0.0);
+        let lan = n_v.y.atan2(n_v.x).rem_euclid(2.0 * PI);
+        let (lan, argp, nu) = match convention {
+            AngleConvention::Classical => (
+                lan,
+                angle_about(n_v, ecc_v, i_h),
+                angle_about(ecc_v, r, i_h),
+            ),
+            AngleConvention::ArgumentOfLatitude => (lan, 0.0, angle_about(n_v, r, i_h)),
+            AngleConvention::LongitudeOfPeriapsis => (
+                0.0,
+                angle_about(i_x, ecc_v, i_h),
+                angle_about(ecc_v, r, i_h),
+            ),
+            AngleConvention::TrueLongitude => (0.0, 0.0, angle_about(i_x, r, i_h)),
         };
 
-        // calculate lan
-        let lan = if n_v.y >= 0.0 {
-            (n_v.x / n_v.mag()).acos()
+        Self {
+            ecc: ecc_v.mag(),
+            a,
+            p,
+            inc,
+            lan,
+            argp,
+            nu,
+            mu,
+            convention,
+        }
+    }
+
+    pub fn perifocal_basis(&self) -> (Vector3D, Vector3D) {
+        // unit vectors of the orbit plane towards periapsis and 90 degrees ahead of it
+        let (sin_lan, cos_lan) = self.lan.sin_cos();
+        let (sin_argp, cos_argp) = self.argp.sin_cos();
+        let (sin_inc, cos_inc) = self.inc.sin_cos();
+        let i_p = Vector3D::new(
+            cos_lan * cos_argp - sin_lan * sin_argp * cos_inc,
+            sin_lan * cos_argp + cos_lan * sin_argp * cos_inc,
+            sin_argp * sin_inc,
+        );
+        let i_q = Vector3D::new(
+            -cos_lan * sin_argp - sin_lan * cos_argp * cos_inc,
+            -sin_lan * sin_argp + cos_lan * cos_argp * cos_inc,
+            cos_argp * sin_inc,
+        );
+        (i_p, i_q)
+    }
+
+    pub fn to_rv(&self, mu: f64) -> (Vector3D, Vector3D) {
+        // position and velocity vectors at the current true anomaly about a body
+        // with gravitational parameter mu
+        let (i_p, i_q) = self.perifocal_basis();
+        let (sin_nu, cos_nu) = self.nu.sin_cos();
+        let r = self.p / (1.0 + self.ecc * cos_nu);
+        let v = (mu / self.p).sqrt();
+        (
+            r * cos_nu * i_p + r * sin_nu * i_q,
+            -v * sin_nu * i_p + v * (self.ecc + cos_nu) * i_q,
+        )
+    }
+
+    pub fn argument_of_latitude(&self) -> f64 {
+        (self.argp + self.nu).rem_euclid(2.0 * PI)
+    }
+
+    pub fn longitude_of_periapsis(&self) -> f64 {
+        // measured about the orbit normal, so lan is subtracted for retrograde orbits
+        if self.inc > PI / 2.0 {
+            (self.argp - self.lan).rem_euclid(2.0 * PI)
         } else {
-            2.0 * PI - (n_v.x / n_v.mag()).acos()
-        };
+            (self.lan + self.argp).rem_euclid(2.0 * PI)
+        }
+    }
+
+    pub fn true_longitude(&self) -> f64 {
+        (self.longitude_of_periapsis() + self.nu).rem_euclid(2.0 * PI)
+    }
+
+    pub fn period(&self) -> f64 {
+        // infinite for open orbits
+        match Conic::from_ecc(self.ecc) {
+            Conic::Elliptic => 2.0 * PI / self.mean_motion(),
+            _ => f64::INFINITY,
+        }
+    }
+
+    pub fn specific_energy(&self) -> f64 {
+        // -mu / 2a written with p, so it is zero for parabolic orbits
+        -self.mu * (1.0 - self.ecc.powf(2.0)) / (2.0 * self.p)
+    }
+
+    pub fn specific_angular_momentum(&self) -> f64 {
+        (self.mu * self.p).sqrt()
+    }
+
+    pub fn radius(&self) -> f64 {
+        // distance from the central body at the current true anomaly
+        self.radius_at(self.nu)
+    }
+
+    pub fn radius_at(&self, nu: f64) -> f64 {
+        // negative beyond the asymptotes of open orbits
+        self.p / (1.0 + self.ecc * nu.cos())
+    }
+
+    pub fn position_at(&self, nu: f64) -> Vector3D {
+        // position vector at true anomaly nu on the same conic
+        let (i_p, i_q) = self.perifocal_basis();
+        let r = self.radius_at(nu);
+        r * nu.cos() * i_p + r * nu.sin() * i_q
+    }
+
+    pub fn periapsis_radius(&self) -> f64 {
+        self.p / (1.0 + self.ecc)
+    }
+
+    pub fn apoapsis_radius(&self) -> f64 {
+        // infinite for open orbits
+        match Conic::from_ecc(self.ecc) {
+            Conic::Elliptic => self.p / (1.0 - self.ecc),
+            _ => f64::INFINITY,
+        }
+    }
+
+    pub fn periapsis_altitude(&self, body_radius: f64) -> f64 {
+        self.periapsis_radius() - body_radius
+    }
+
+    pub fn apoapsis_altitude(&self, body_radius: f64) -> f64 {
+        self.apoapsis_radius() - body_radius
+    }
+
+    pub fn flight_path_angle(&self) -> f64 {
+        // angle of the velocity above the local horizontal, positive moving away from periapsis
+        let (sin_nu, cos_nu) = self.nu.sin_cos();
+        (self.ecc * sin_nu).atan2(1.0 + self.ecc * cos_nu)
+    }
+
+    pub fn time_since_periapsis(&self) -> f64 {
+        // in [0, period) for elliptic orbits, negative before periapsis on open ones
+        self.time_of_flight(0.0, self.nu)
+    }
+
+    pub fn time_to_periapsis(&self) -> f64 {
+        // in [0, period) for elliptic orbits, negative after periapsis on open ones
+        self.time_of_flight(self.nu, 0.0)
+    }
+
+    pub fn mean_motion(&self) -> f64 {
+        match Conic::from_ecc(self.ecc) {
+            Conic::Parabolic => 2.0 * (self.mu / self.p.powf(3.0)).sqrt(),
+            _ => (self.mu / self.a.abs().powf(3.0)).sqrt(),
+        }
+    }
+
+    pub fn mean_anomaly(&self) -> f64 {
+        kepler::true_to_mean(self.nu, self.ecc)
+    }
+
+    pub fn time_of_flight(&self, nu1: f64, nu2: f64) -> f64 {
+        // time to travel from true anomaly nu1 to nu2. Elliptic orbits always move
+        // forward (less than a period), on open orbits it is negative if nu2 precedes nu1.
+        let dm = kepler::true_to_mean(nu2, self.ecc) - kepler::true_to_mean(nu1, self.ecc);
+        match Conic::from_ecc(self.ecc) {
+            Conic::Elliptic => dm.rem_euclid(2.0 * PI) / self.mean_motion(),
+            _ => dm / self.mean_motion(),
+        }
+    }
 
+    pub fn propagate(&self, dt: f64) -> Self {
+        // orbit with true anomaly advanced by time dt
+        let m = self.mean_anomaly() + self.mean_motion() * dt;
         Self {
-            ecc: ecc_v.mag(),
-            a: a,
-            inc: inc,
-            lan: lan,
-            argp: (ecc_v * n_v / n_v.mag() / ecc_v.mag()).acos(),
-            nu: nu,
+            nu: kepler::mean_to_true(m, self.ecc).rem_euclid(2.0 * PI),
+            ..*self
+        }
+    }
+}
+
+fn angle_about(from: Vector3D, to: Vector3D, axis: Vector3D) -> f64 {
+    // angle in [0, 2 pi) from one vector to another, counterclockwise about axis
+    (axis * from.cross(to))
+        .atan2(from * to)
+        .rem_euclid(2.0 * PI)
+}
+
+#[cfg(test)]
+#[test]
+fn test_propagate_lambert() {
+    // propagating r1, v1 for the time-of-flight lands on r2
+    let mu = 3.986004e5;
+    let cases = [
+        (
+            Vector3D::new(5000.0, 10000.0, 2100.0),
+            Vector3D::new(-14600.0, 2500.0, 7000.0),
+            3600.0,
+        ),
+        // hyperbolic
+        (
+            Vector3D::new(7000.0, 0.0, 0.0),
+            Vector3D::new(0.0, 8000.0, 500.0),
+            600.0,
+        ),
+    ];
+    for &(r1, r2, tof) in &cases {
+        let ls = crate::LambertSolver::new(r1, r2, tof, mu).unwrap();
+        let departure = Orbit::from_rv(r1, ls.get_v1(), mu);
+        let arrival = Orbit::from_rv(r2, ls.get_v2(), mu);
+        assert!((departure.propagate(tof).nu - arrival.nu).abs() < 1e-9);
+        assert!((departure.time_of_flight(departure.nu, arrival.nu) - tof).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_propagate_period() {
+    let orbit = Orbit {
+        ecc: 0.3,
+        a: 10000.0,
+        p: 10000.0 * (1.0 - 0.3f64.powf(2.0)),
+        nu: 1.0,
+        mu: 3.986004e5,
+        ..Default::default()
+    };
+    let period = 2.0 * PI / orbit.mean_motion();
+
+    // a full period returns to the same state
+    let (r, v) = orbit.to_rv(orbit.mu);
+    let (r_new, v_new) = orbit.propagate(period).to_rv(orbit.mu);
+    assert!((r_new - r).mag() / r.mag() < 1e-12);
+    assert!((v_new - v).mag() / v.mag() < 1e-12);
+
+    // half a period from periapsis reaches apoapsis
+    let periapsis = Orbit { nu: 0.0, ..orbit };
+    assert!((periapsis.propagate(period / 2.0).nu - PI).abs() < 1e-12);
+
+    // going back from nu = 1 to 0.5 takes all of the period but the way forward
+    let back = orbit.time_of_flight(1.0, 0.5);
+    assert!((back + orbit.time_of_flight(0.5, 1.0) - period).abs() / period < 1e-12);
+    assert!((orbit.propagate(back).nu - 0.5).abs() < 1e-12);
+}
+
+#[test]
+fn test_propagate_parabolic() {
+    let mu = 3.986004e5;
+    let p: f64 = 14000.0;
+    let orbit = Orbit {
+        ecc: 1.0,
+        a: f64::INFINITY,
+        p,
+        mu,
+        ..Default::default()
+    };
+    // time from periapsis to nu = 90 deg from Barker's equation
+    let t = 0.5 * (p.powf(3.0) / mu).sqrt() * (1.0 + 1.0 / 3.0);
+    assert!((orbit.propagate(t).nu - PI / 2.0).abs() < 1e-12);
+    assert!((orbit.time_of_flight(0.0, PI / 2.0) - t).abs() < 1e-9);
+}
+
+#[cfg(test)]
+fn angle_diff(a: f64, b: f64) -> f64 {
+    ((a - b + PI).rem_euclid(2.0 * PI) - PI).abs()
+}
+
+#[test]
+fn test_to_rv() {
+    let mu = 3.986004e5;
+    let r = Vector3D::new(5000.0, 10000.0, 2100.0);
+    let v = Vector3D::new(-5.992494984068112, 1.925366402070909, 3.2456379064882404);
+    let (r_new, v_new) = Orbit::from_rv(r, v, mu).to_rv(mu);
+    assert!((r_new - r).mag() < 1e-8);
+    assert!((v_new - v).mag() < 1e-12);
+
+    // circular equatorial orbit with all angles zero starts on X axis
+    let (r, v) = Orbit::new(7000.0, 0.0, 0.0, 0.0, 0.0, 0.0, mu).to_rv(mu);
+    assert!((r - Vector3D::new(7000.0, 0.0, 0.0)).mag() < 1e-9);
+    assert!((v - Vector3D::new(0.0, (mu / 7000.0).sqrt(), 0.0)).mag() < 1e-12);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_elements_round_trip(
+        a in 7000.0..100000.0f64,
+        ecc in 0.01..0.95f64,
+        inc in 0.01..3.13f64,
+        lan in 0.01..(2.0 * PI),
+        argp in 0.01..(2.0 * PI),
+        nu in 0.0..(2.0 * PI),
+    ) {
+        let mu = 3.986004e5;
+        let orbit = Orbit::new(a, ecc, inc, lan, argp, nu, mu);
+        let (r, v) = orbit.to_rv(mu);
+        let new = Orbit::from_rv(r, v, mu);
+        proptest::prop_assert!((new.a - a).abs() / a < 1e-10);
+        proptest::prop_assert!((new.ecc - ecc).abs() < 1e-10);
+        proptest::prop_assert!(angle_diff(new.inc, inc) < 1e-9);
+        proptest::prop_assert!(angle_diff(new.lan, lan) < 1e-9);
+        proptest::prop_assert!(angle_diff(new.argp, argp) < 1e-8);
+        proptest::prop_assert!(angle_diff(new.nu, nu) < 1e-8);
+        let (r_new, v_new) = new.to_rv(mu);
+        proptest::prop_assert!((r_new - r).mag() / r.mag() < 1e-12);
+        proptest::prop_assert!((v_new - v).mag() / v.mag() < 1e-12);
+    }
+
+    #[test]
+    fn test_hyperbolic_round_trip(
+        a in -100000.0..-7000.0f64,
+        ecc in 1.05..5.0f64,
+        inc in 0.01..3.13f64,
+        lan in 0.01..(2.0 * PI),
+        argp in 0.01..(2.0 * PI),
+        nu_fraction in -0.95..0.95f64,
+    ) {
+        let mu = 3.986004e5;
+        let nu = nu_fraction * (-1.0 / ecc).acos();
+        let (r, v) = Orbit::new(a, ecc, inc, lan, argp, nu, mu).to_rv(mu);
+        let new = Orbit::from_rv(r, v, mu);
+        proptest::prop_assert!((new.a - a).abs() / a.abs() < 1e-10);
+        proptest::prop_assert!((new.ecc - ecc).abs() < 1e-10);
+        proptest::prop_assert!(angle_diff(new.nu, nu) < 1e-8);
+        let (r_new, v_new) = new.to_rv(mu);
+        proptest::prop_assert!((r_new - r).mag() / r.mag() < 1e-12);
+        proptest::prop_assert!((v_new - v).mag() / v.mag() < 1e-12);
+    }
+}
+
+#[test]
+fn test_singular_orbits() {
+    let mu = 3.986004e5f64;
+    let v_c = (mu / 7000.0).sqrt();
+    let r = Vector3D::new(0.0, 7000.0, 0.0);
+    let cases = [
+        // circular inclined
+        (
+            r,
+            Vector3D::new(-v_c * 0.6, 0.0, v_c * 0.8),
+            AngleConvention::ArgumentOfLatitude,
+        ),
+        // equatorial elliptic
+        (
+            r,
+            Vector3D::new(-v_c * 1.1, 0.5, 0.0),
+            AngleConvention::LongitudeOfPeriapsis,
+        ),
+        // circular equatorial
+        (
+            r,
+            Vector3D::new(-v_c, 0.0, 0.0),
+            AngleConvention::TrueLongitude,
+        ),
+        // circular retrograde equatorial
+        (
+            r,
+            Vector3D::new(v_c, 0.0, 0.0),
+            AngleConvention::TrueLongitude,
+        ),
+    ];
+    for &(r, v, convention) in &cases {
+        let orbit = Orbit::from_rv(r, v, mu);
+        assert_eq!(orbit.convention, convention);
+        for &value in &[
+            orbit.ecc, orbit.a, orbit.inc, orbit.lan, orbit.argp, orbit.nu,
+        ] {
+            assert!(value.is_finite());
         }
+        let (r_new, v_new) = orbit.to_rv(mu);
+        assert!((r_new - r).mag() < 1e-8);
+        assert!((v_new - v).mag() < 1e-12);
     }
+
+    let orbit = Orbit::from_rv(r, Vector3D::new(-v_c, 0.0, 0.0), mu);
+    assert!((orbit.inc).abs() < 1e-12);
+    assert!((orbit.nu - PI / 2.0).abs() < 1e-12);
+    assert!((orbit.true_longitude() - PI / 2.0).abs() < 1e-12);
+    let orbit = Orbit::from_rv(r, Vector3D::new(v_c, 0.0, 0.0), mu);
+    assert!((orbit.inc - PI).abs() < 1e-12);
+    assert!((orbit.true_longitude() - 3.0 * PI / 2.0).abs() < 1e-12);
+    let orbit = Orbit::from_rv(r, Vector3D::new(-v_c * 0.6, 0.0, v_c * 0.8), mu);
+    assert!((orbit.lan - PI / 2.0).abs() < 1e-12);
+    assert!(orbit.nu.abs() < 1e-12);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn test_singular_round_trip(
+        a in 7000.0..100000.0f64,
+        ecc in 0.01..0.95f64,
+        inc in 0.01..3.13f64,
+        angle in 0.0..(2.0 * PI),
+        nu in 0.0..(2.0 * PI),
+        retrograde: bool,
+    ) {
+        let mu = 3.986004e5;
+        let equatorial_inc = if retrograde { PI } else { 0.0 };
+        let orbits = [
+            Orbit::new(a, 0.0, inc, angle, 0.0, nu, mu),
+            Orbit::new(a, ecc, equatorial_inc, 0.0, angle, nu, mu),
+            Orbit::new(a, 0.0, equatorial_inc, 0.0, 0.0, nu, mu),
+        ];
+        for orbit in &orbits {
+            let (r, v) = orbit.to_rv(mu);
+            let new = Orbit::from_rv(r, v, mu);
+            proptest::prop_assert_eq!(new.convention, orbit.convention);
+            proptest::prop_assert!(angle_diff(new.lan, orbit.lan) < 1e-9);
+            proptest::prop_assert!(angle_diff(new.argp, orbit.argp) < 1e-8);
+            proptest::prop_assert!(angle_diff(new.nu, orbit.nu) < 1e-8);
+            let (r_new, v_new) = new.to_rv(mu);
+            proptest::prop_assert!((r_new - r).mag() / r.mag() < 1e-12);
+            proptest::prop_assert!((v_new - v).mag() / v.mag() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_derived_quantities() {
+    let mu = 3.986004e5;
+    let orbit = Orbit::new(10000.0, 0.2, 0.5, 1.0, 2.0, PI / 2.0, mu);
+    let period = 2.0 * PI * (10000.0f64.powf(3.0) / mu).sqrt();
+    assert!((orbit.period() - period).abs() < 1e-9);
+    assert!((orbit.specific_energy() + mu / 20000.0).abs() < 1e-12);
+    assert!((orbit.periapsis_radius() - 8000.0).abs() < 1e-9);
+    assert!((orbit.apoapsis_radius() - 12000.0).abs() < 1e-9);
+    assert!((orbit.periapsis_altitude(6378.0) - 1622.0).abs() < 1e-9);
+    assert!((orbit.apoapsis_altitude(6378.0) - 5622.0).abs() < 1e-9);
+    assert!((orbit.flight_path_angle() - 0.2f64.atan()).abs() < 1e-12);
+    assert!((orbit.time_since_periapsis() + orbit.time_to_periapsis() - period).abs() < 1e-9);
+
+    // consistency with the state vectors, including an open orbit
+    let hyperbolic = Orbit::new(-20000.0, 1.5, 0.5, 1.0, 2.0, 5.0, mu);
+    assert_eq!(hyperbolic.period(), f64::INFINITY);
+    assert_eq!(hyperbolic.apoapsis_radius(), f64::INFINITY);
+    assert!(hyperbolic.time_since_periapsis() < 0.0);
+    assert!((hyperbolic.time_since_periapsis() + hyperbolic.time_to_periapsis()).abs() < 1e-9);
+    for orbit in &[orbit, hyperbolic] {
+        let (r, v) = orbit.to_rv(mu);
+        assert!((orbit.radius() - r.mag()).abs() < 1e-8);
+        assert!((orbit.specific_energy() - (v * v / 2.0 - mu / r.mag())).abs() < 1e-9);
+        assert!((orbit.specific_angular_momentum() - r.cross(v).mag()).abs() < 1e-8);
+        let fpa = (r * v / (r.mag() * v.mag())).asin();
+        assert!((orbit.flight_path_angle() - fpa).abs() < 1e-12);
+
+        let (i_p, i_q) = orbit.perifocal_basis();
+        assert!((i_p * i_q).abs() < 1e-12 && (i_p.mag() - 1.0).abs() < 1e-12);
+        let nu = (r * i_q).atan2(r * i_p);
+        assert!(angle_diff(nu, orbit.nu) < 1e-12);
+        let later = orbit.propagate(orbit.time_of_flight(nu, nu + 0.1));
+        assert!((orbit.radius_at(nu + 0.1) - later.radius()).abs() < 1e-6);
+        assert!((orbit.position_at(nu + 0.1) - later.to_rv(mu).0).mag() < 1e-6);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let orbit = Orbit::new(10000.0, 0.0, 0.5, 1.0, 0.0, 2.0, 3.986004e5);
+    let json = serde_json::to_string(&orbit).unwrap();
+    assert!(json.contains("\"convention\":\"ArgumentOfLatitude\""));
+    assert_eq!(serde_json::from_str::<Orbit>(&json).unwrap(), orbit);
 }
@@ -0,0 +1,307 @@
+// Porkchop plots: Lambert transfers between two planets over a grid of departure
+// and arrival epochs, with the launch and arrival energy of each transfer
+use std::io::{self, Write};
+
+use crate::bodies;
+use crate::csv::csv_field;
+use crate::ephemeris::Planet;
+use crate::lambert::{self, LambertError, LambertOptions, LambertSolution};
+use crate::time::{Duration, Epoch, TimeScale};
+use crate::Vector3D;
+
+const CSV_HEADER: &str = "departure,arrival,departure_mjd,arrival_mjd,tof_days,\
+                          c3,v_inf_departure,v_inf_arrival,dv,error";
+
+// Hyperbolic excess velocities are taken relative to the planets' heliocentric
+// velocities, the total delta-v is the sum of the departure and arrival v-infinity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PorkchopTransfer {
+    pub solution: LambertSolution,
+    // departure and arrival hyperbolic excess velocity vectors (km/s)
+    pub v_inf_departure: Vector3D,
+    pub v_inf_arrival: Vector3D,
+}
+
+impl PorkchopTransfer {
+    pub fn c3(&self) -> f64 {
+        // characteristic energy of the departure hyperbola (km^2 / s^2)
+        self.v_inf_departure * self.v_inf_departure
+    }
+
+    pub fn delta_v(&self) -> f64 {
+        self.v_inf_departure.mag() + self.v_inf_arrival.mag()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PorkchopNode {
+    pub departure: Epoch,
+    pub arrival: Epoch,
+    pub outcome: Result<PorkchopTransfer, LambertError>,
+}
+
+impl PorkchopNode {
+    pub fn time_of_flight(&self) -> Duration {
+        self.arrival - self.departure
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Porkchop {
+    pub departure_planet: Planet,
+    pub arrival_planet: Planet,
+    pub departures: Vec<Epoch>,
+    pub arrivals: Vec<Epoch>,
+    // row-major, one row of arrivals for each departure
+    pub nodes: Vec<PorkchopNode>,
+}
+
+impl Porkchop {
+    pub fn sweep(
+        departure_planet: Planet,
+        departures: Vec<Epoch>,
+        arrival_planet: Planet,
+        arrivals: Vec<Epoch>,
+        options: &LambertOptions,
+    ) -> Self {
+        // nodes arriving before departure or outside the ephemeris fail with InvalidInput
+        let departure_states: Vec<_> = departures
+            .iter()
+            .map(|&epoch| departure_planet.state(epoch))
+            .collect();
+        let arrival_states: Vec<_> = arrivals
+            .iter()
+            .map(|&epoch| arrival_planet.state(epoch))
+            .collect();
+
+        let mut nodes = Vec::with_capacity(departures.len() * arrivals.len());
+        for (&departure, &departure_state) in departures.iter().zip(&departure_states) {
+            for (&arrival, &arrival_state) in arrivals.iter().zip(&arrival_states) {
+                nodes.push(PorkchopNode {
+                    departure,
+                    arrival,
+                    outcome: transfer(departure, departure_state, arrival, arrival_state, options),
+                });
+            }
+        }
+        Self {
+            departure_planet,
+            arrival_planet,
+            departures,
+            arrivals,
+            nodes,
+        }
+    }
+
+    pub fn node(&self, departure: usize, arrival: usize) -> &PorkchopNode {
+        &self.nodes[departure * self.arrivals.len() + arrival]
+    }
+
+    pub fn min_c3(&self) -> Option<&PorkchopNode> {
+        self.min_by(PorkchopTransfer::c3)
+    }
+
+    pub fn min_delta_v(&self) -> Option<&PorkchopNode> {
+        self.min_by(PorkchopTransfer::delta_v)
+    }
+
+    fn min_by(&self, cost: impl Fn(&PorkchopTransfer) -> f64) -> Option<&PorkchopNode> {
+        self.nodes
+            .iter()
+            .filter_map(|node| node.outcome.as_ref().ok().map(|t| (node, cost(t))))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(node, _)| node)
+    }
+
+    pub fn contour(
+        &self,
+        value: impl Fn(&PorkchopNode) -> Option<f64>,
+        level: f64,
+    ) -> Vec<[(f64, f64); 2]> {
+        // line segments where value crosses level by marching squares, as fractional
+        // (departure, arrival) grid indices. Cells with a failed node are skipped and
+        // saddle cells are split along the first pair of crossed edges.
+        let values: Vec<_> = self.nodes.iter().map(value).collect();
+        let at = |i: usize, j: usize| values[i * self.arrivals.len() + j];
+        let mut segments = Vec::new();
+        for i in 1..self.departures.len() {
+            for j in 1..self.arrivals.len() {
+                let corners = [(i - 1, j - 1), (i, j - 1), (i, j), (i - 1, j)];
+                let corner_values: Option<Vec<f64>> =
+                    corners.iter().map(|&(i, j)| at(i, j)).collect();
+                let corner_values = match corner_values {
+                    Some(values) => values,
+                    None => continue,
+                };
+                let mut crossings = Vec::new();
+                for k in 0..4 {
+                    let (a, b) = (corner_values[k], corner_values[(k + 1) % 4]);
+                    if (a < level) != (b < level) {
+                        let s = (level - a) / (b - a);
+                        let ((i0, j0), (i1, j1)) = (corners[k], corners[(k + 1) % 4]);
+                        crossings.push((
+                            i0 as f64 + s * (i1 as f64 - i0 as f64),
+                            j0 as f64 + s * (j1 as f64 - j0 as f64),
+                        ));
+                    }
+                }
+                segments.extend(crossings.chunks_exact(2).map(|p| [p[0], p[1]]));
+            }
+        }
+        segments
+    }
+
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        // one row per node, epochs as UTC and TDB modified Julian dates, velocities
+        // in km/s and C3 in km^2/s^2, empty for nodes without a solution
+        writeln!(writer, "{}", CSV_HEADER)?;
+        for node in &self.nodes {
+            let mut fields = vec![
+                node.departure.to_string(),
+                node.arrival.to_string(),
+                node.departure.mjd(TimeScale::Tdb).to_string(),
+                node.arrival.mjd(TimeScale::Tdb).to_string(),
+                node.time_of_flight().days().to_string(),
+            ];
+            match &node.outcome {
+                Ok(t) => {
+                    fields.extend(
+                        [
+                            t.c3(),
+                            t.v_inf_departure.mag(),
+                            t.v_inf_arrival.mag(),
+                            t.delta_v(),
+                        ]
+                        .iter()
+                        .map(f64::to_string),
+                    );
+                    fields.push(String::new());
+                }
+                Err(e) => {
+                    fields.extend(vec![String::new(); 4]);
+                    fields.push(csv_field(&e.to_string()));
+                }
+            }
+            writeln!(writer, "{}", fields.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn epoch_range(start: Epoch, end: Epoch, step: Duration) -> Vec<Epoch> {
+    // start, start + step, .. up to and including end
+    if step.seconds() <= 0.0 {
+        return vec![start];
+    }
+    let steps = ((end - start).seconds() / step.seconds() + 1e-9)
+        .floor()
+        .max(0.0) as usize;
+    (0..=steps)
+        .map(|i| start + Duration::from_seconds(step.seconds() * i as f64))
+        .collect()
+}
+
+fn transfer(
+    departure: Epoch,
+    departure_state: Option<(Vector3D, Vector3D)>,
+    arrival: Epoch,
+    arrival_state: Option<(Vector3D, Vector3D)>,
+    options: &LambertOptions,
+) -> Result<PorkchopTransfer, LambertError> {
+    let (r1, v_planet1) = departure_state.ok_or(LambertError::InvalidInput {
+        parameter: "departure date",
+        value: departure.jd(TimeScale::Tdb),
+    })?;
+    let (r2, v_planet2) = arrival_state.ok_or(LambertError::InvalidInput {
+        parameter: "arrival date",
+        value: arrival.jd(TimeScale::Tdb),
+    })?;
+    let tof = (arrival - departure).seconds();
+    let solution = lambert::solve(r1, r2, tof, bodies::SUN.mu, options)?;
+    Ok(PorkchopTransfer {
+        solution,
+        v_inf_departure: solution.v1 - v_planet1,
+        v_inf_arrival: solution.v2 - v_planet2,
+    })
+}
+
+#[cfg(test)]
+#[test]
+fn test_epoch_range() {
+    let start: Epoch = "2020-07-01".parse().unwrap();
+    let end: Epoch = "2020-07-11".parse().unwrap();
+    let range = epoch_range(start, end, Duration::from_days(5.0));
+    assert_eq!(range, vec![start, start + Duration::from_days(5.0), end]);
+    assert_eq!(epoch_range(start, end, Duration::from_days(3.0)).len(), 4);
+    assert_eq!(epoch_range(end, start, Duration::from_days(1.0)), vec![end]);
+}
+
+#[test]
+fn test_earth_mars_2020() {
+    // the 2020 window opened in July with C3 of about 13-15 km^2/s^2
+    let departures = epoch_range(
+        "2020-06-01".parse().unwrap(),
+        "2020-09-29".parse().unwrap(),
+        Duration::from_days(10.0),
+    );
+    let arrivals = epoch_range(
+        "2020-09-01".parse().unwrap(),
+        "2021-08-29".parse().unwrap(),
+        Duration::from_days(20.0),
+    );
+    let porkchop = Porkchop::sweep(
+        Planet::Earth,
+        departures,
+        Planet::Mars,
+        arrivals,
+        &LambertOptions::default(),
+    );
+    assert_eq!(porkchop.nodes.len(), 13 * 19);
+    assert_eq!(porkchop.node(1, 2).departure, porkchop.departures[1]);
+    assert_eq!(porkchop.node(1, 2).arrival, porkchop.arrivals[2]);
+
+    let best = porkchop.min_c3().unwrap();
+    let c3 = best.outcome.as_ref().unwrap().c3();
+    assert!(c3 > 8.0 && c3 < 16.0, "{}", c3);
+    let departure = best.departure.datetime(TimeScale::Utc);
+    assert_eq!(departure.year, 2020);
+    assert!((7..=8).contains(&departure.month));
+    assert!(porkchop.min_delta_v().unwrap().outcome.is_ok());
+
+    let mut csv = Vec::new();
+    porkchop.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 1 + porkchop.nodes.len());
+    assert_eq!(lines[0], CSV_HEADER);
+    assert!(lines[1].starts_with("2020-06-01T00:00:00.000Z,2020-09-01T00:00:00.000Z,"));
+    assert!(lines
+        .iter()
+        .all(|line| line.split(',').count() >= CSV_HEADER.split(',').count()));
+}
+
+#[test]
+fn test_contour() {
+    // time of flight grows by one step for every arrival and falls for every departure
+    let start: Epoch = "2020-07-01".parse().unwrap();
+    let step = Duration::from_days(10.0);
+    let porkchop = Porkchop::sweep(
+        Planet::Earth,
+        epoch_range(start, start + Duration::from_days(30.0), step),
+        Planet::Mars,
+        epoch_range(
+            start + Duration::from_days(150.0),
+            start + Duration::from_days(200.0),
+            step,
+        ),
+        &LambertOptions::default(),
+    );
+    let tof = |node: &PorkchopNode| Some(node.time_of_flight().days());
+    let segments = porkchop.contour(tof, 175.0);
+    assert!(!segments.is_empty());
+    for &(i, j) in segments.iter().flatten() {
+        assert!((150.0 + 10.0 * (j - i) - 175.0).abs() < 1e-6);
+    }
+    assert!(porkchop.contour(tof, 300.0).is_empty());
+    assert!(porkchop.contour(|_| None, 175.0).is_empty());
+}
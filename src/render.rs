@@ -0,0 +1,482 @@
+// Software renderer for the 3D orbit view: perspective orbit camera, z-buffered
+// lines and a shaded central body sphere drawn into an RGB image, which can be
+// written as an uncompressed PNG.
+use std::f64::consts::PI;
+use std::io::{self, Write};
+
+#[cfg(feature = "gui")]
+use druid::{Data, Lens};
+
+use crate::kepler::Conic;
+use crate::{LambertSolver, Orbit, Vector3D};
+
+// vertical field of view of the camera
+const FIELD_OF_VIEW: f64 = PI / 4.0;
+// camera distance from the origin in scene extents
+const CAMERA_DISTANCE: f64 = 4.0;
+// points along each orbit
+const ORBIT_SAMPLES: usize = 360;
+const BACKGROUND: [u8; 3] = [0x20, 0x20, 0x20];
+
+// Looks at the origin from azimuth yaw about +Z and elevation pitch (radians),
+// zoom scales the focal length
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+pub struct Camera {
+    pub yaw: f64,
+    pub pitch: f64,
+    pub zoom: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            yaw: -PI / 3.0,
+            pitch: PI / 6.0,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Camera {
+    pub fn rotate(&mut self, d_yaw: f64, d_pitch: f64) {
+        // pitch stops short of the poles, where the view up direction is undefined
+        let limit = PI / 2.0 - 1e-3;
+        self.yaw = (self.yaw + d_yaw).rem_euclid(2.0 * PI);
+        self.pitch = (self.pitch + d_pitch).clamp(-limit, limit);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve {
+    pub points: Vec<Vector3D>,
+    pub color: [u8; 3],
+    // line width in pixels
+    pub width: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    pub body_radius: f64,
+    pub body_color: [u8; 3],
+    // radius around the origin that fits the view at zoom 1
+    pub extent: f64,
+    pub curves: Vec<Curve>,
+}
+
+impl Scene {
+    pub fn new(body_radius: f64, extent: f64) -> Self {
+        // with X, Y and Z axes in red, green and blue
+        let axis = |direction: Vector3D, color| Curve {
+            points: vec![Vector3D::default(), 1.2 * extent * direction],
+            color,
+            width: 1,
+        };
+        Self {
+            body_radius,
+            body_color: [0x40, 0x80, 0xd0],
+            extent,
+            curves: vec![
+                axis(Vector3D::new(1.0, 0.0, 0.0), [0xd0, 0x40, 0x40]),
+                axis(Vector3D::new(0.0, 1.0, 0.0), [0x40, 0xc0, 0x40]),
+                axis(Vector3D::new(0.0, 0.0, 1.0), [0x50, 0x70, 0xf0]),
+            ],
+        }
+    }
+
+    pub fn axis_tips(&self) -> [Vector3D; 3] {
+        [
+            Vector3D::new(1.2 * self.extent, 0.0, 0.0),
+            Vector3D::new(0.0, 1.2 * self.extent, 0.0),
+            Vector3D::new(0.0, 0.0, 1.2 * self.extent),
+        ]
+    }
+
+    pub fn project(
+        &self,
+        camera: &Camera,
+        width: usize,
+        height: usize,
+        point: Vector3D,
+    ) -> Option<(f64, f64)> {
+        // pixel position of a point, None behind the camera
+        Projection::new(self, camera, width, height)
+            .project(point)
+            .map(|(x, y, _)| (x, y))
+    }
+
+    pub fn render(&self, camera: &Camera, width: usize, height: usize) -> Image {
+        let projection = Projection::new(self, camera, width, height);
+        let mut image = Image::new(width, height);
+        let mut depth = vec![f64::INFINITY; width * height];
+
+        // central body by ray casting each pixel, lit from over the camera's shoulder
+        let light = (-projection.forward + 0.6 * projection.up - 0.4 * projection.right).norm();
+        for y in 0..height {
+            for x in 0..width {
+                let direction = projection.ray(x as f64 + 0.5, y as f64 + 0.5);
+                let b = projection.eye * direction;
+                let c = projection.eye * projection.eye - self.body_radius.powf(2.0);
+                let discriminant = b * b - c;
+                if discriminant < 0.0 || self.body_radius <= 0.0 {
+                    continue;
+                }
+                let t = -b - discriminant.sqrt();
+                let normal = (projection.eye + t * direction).norm();
+                let shade = 0.25 + 0.75 * (normal * light).max(0.0);
+                let color = self.body_color.map(|c| (f64::from(c) * shade) as u8);
+                image.set(x, y, color);
+                depth[y * width + x] = t * (direction * projection.forward);
+            }
+        }
+
+        for curve in &self.curves {
+            let points: Vec<_> = curve
+                .points
+                .iter()
+                .map(|&p| projection.project(p))
+                .collect();
+            for segment in points.windows(2) {
+                if let [Some(a), Some(b)] = segment {
+                    image.line(&mut depth, *a, *b, curve.color, curve.width);
+                }
+            }
+        }
+        image
+    }
+}
+
+struct Projection {
+    eye: Vector3D,
+    forward: Vector3D,
+    right: Vector3D,
+    up: Vector3D,
+    focal: f64,
+    center: (f64, f64),
+}
+
+impl Projection {
+    fn new(scene: &Scene, camera: &Camera, width: usize, height: usize) -> Self {
+        let (sin_yaw, cos_yaw) = camera.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = camera.pitch.sin_cos();
+        let eye = CAMERA_DISTANCE
+            * scene.extent
+            * Vector3D::new(cos_pitch * cos_yaw, cos_pitch * sin_yaw, sin_pitch);
+        let forward = (-eye).norm();
+        let right = forward.cross(Vector3D::new(0.0, 0.0, 1.0)).norm();
+        let up = right.cross(forward);
+        let focal = 0.5 * width.min(height) as f64 / (FIELD_OF_VIEW / 2.0).tan() * camera.zoom;
+        Self {
+            eye,
+            forward,
+            right,
+            up,
+            focal,
+            center: (width as f64 / 2.0, height as f64 / 2.0),
+        }
+    }
+
+    fn project(&self, point: Vector3D) -> Option<(f64, f64, f64)> {
+        // pixel x, y and depth along the view direction
+        let d = point - self.eye;
+        let z = d * self.forward;
+        if z <= 0.0 {
+            return None;
+        }
+        Some((
+            self.center.0 + self.focal * (d * self.right) / z,
+            self.center.1 - self.focal * (d * self.up) / z,
+            z,
+        ))
+    }
+
+    fn ray(&self, x: f64, y: f64) -> Vector3D {
+        (self.forward + (x - self.center.0) / self.focal * self.right
+            - (y - self.center.1) / self.focal * self.up)
+            .norm()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    // RGB, row by row from the top
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: BACKGROUND.repeat(width * height),
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> [u8; 3] {
+        let i = 3 * (y * self.width + x);
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    fn set(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        let i = 3 * (y * self.width + x);
+        self.pixels[i..i + 3].copy_from_slice(&color);
+    }
+
+    fn line(
+        &mut self,
+        depth: &mut [f64],
+        a: (f64, f64, f64),
+        b: (f64, f64, f64),
+        color: [u8; 3],
+        width: usize,
+    ) {
+        // steps of at most one pixel, each drawn as a width x width square that is
+        // hidden behind nearer pixels
+        let steps = (b.0 - a.0).abs().max((b.1 - a.1).abs()).ceil().min(1e5) as usize;
+        for k in 0..=steps {
+            let s = if steps == 0 {
+                0.0
+            } else {
+                k as f64 / steps as f64
+            };
+            let x = a.0 + s * (b.0 - a.0) - (width as f64 - 1.0) / 2.0;
+            let y = a.1 + s * (b.1 - a.1) - (width as f64 - 1.0) / 2.0;
+            let z = a.2 + s * (b.2 - a.2);
+            for (dx, dy) in (0..width).flat_map(|dx| (0..width).map(move |dy| (dx, dy))) {
+                let (px, py) = ((x + dx as f64).floor(), (y + dy as f64).floor());
+                if px < 0.0 || py < 0.0 || px >= self.width as f64 || py >= self.height as f64 {
+                    continue;
+                }
+                let (px, py) = (px as usize, py as usize);
+                // lines on the body surface stay visible
+                if z <= depth[py * self.width + px] * (1.0 + 1e-6) {
+                    depth[py * self.width + px] = z;
+                    self.set(px, py, color);
+                }
+            }
+        }
+    }
+
+    pub fn write_png<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        // 8 bit RGB without filtering, in stored (uncompressed) deflate blocks
+        let mut raw = Vec::with_capacity((3 * self.width + 1) * self.height);
+        for row in self.pixels.chunks(3 * self.width) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+        let mut zlib = vec![0x78, 0x01];
+        let blocks: Vec<_> = raw.chunks(0xffff).collect();
+        for (i, block) in blocks.iter().enumerate() {
+            let len = block.len() as u16;
+            zlib.push(u8::from(i + 1 == blocks.len()));
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        if blocks.is_empty() {
+            zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // bit depth 8, colour type RGB, deflate, no filter, no interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        writer.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_chunk(&mut writer, b"IHDR", &header)?;
+        write_chunk(&mut writer, b"IDAT", &zlib)?;
+        write_chunk(&mut writer, b"IEND", &[])
+    }
+}
+
+fn write_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    writer.write_all(&crc.to_be_bytes())
+}
+
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    // CRC-32 of the PNG specification, bit by bit
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+pub fn transfer_scene(ls: &LambertSolver) -> Scene {
+    // axes, central body, r1 and r2, the departure and arrival orbits when their
+    // velocities are known, the transfer conic and the transfer arc of the stored
+    // solution
+    let (r1, r2, mu) = (ls.get_r1(), ls.get_r2(), ls.get_mu());
+    let transfer = ls.get_solution().map(|s| Orbit::from_rv(r1, s.v1, mu));
+    let known = |v: Vector3D| if v.mag() > 0.0 { Some(v) } else { None };
+    let departure = known(ls.v_dep).map(|v| Orbit::from_rv(r1, v, mu));
+    let arrival = known(ls.v_arr).map(|v| Orbit::from_rv(r2, v, mu));
+
+    // closed orbits fit the view unless their apoapsis is far out
+    let r_max = r1.mag().max(r2.mag()).max(ls.body_radius);
+    let extent = [&transfer, &departure, &arrival]
+        .iter()
+        .filter_map(|orbit| orbit.as_ref().map(Orbit::apoapsis_radius))
+        .filter(|&apoapsis| apoapsis < 4.0 * r_max)
+        .fold(r_max, f64::max)
+        * 1.15;
+    let mut scene = Scene::new(ls.body_radius, if extent > 0.0 { extent } else { 1.0 });
+
+    let conic = |orbit: &Orbit| {
+        // whole ellipse, open orbits out to 20 extents from the focus
+        let nu_max = match Conic::from_ecc(orbit.ecc) {
+            Conic::Elliptic => PI,
+            _ => ((orbit.p / (20.0 * extent) - 1.0) / orbit.ecc)
+                .clamp(-1.0, 1.0)
+                .acos(),
+        };
+        orbit_points(orbit, -nu_max, nu_max)
+    };
+    let curve = |points, color, width| Curve {
+        points,
+        color,
+        width,
+    };
+    for &r in &[r1, r2] {
+        let points = vec![Vector3D::default(), r];
+        scene.curves.push(curve(points, [0xa0, 0xa0, 0xa0], 1));
+    }
+    if let Some(orbit) = &departure {
+        scene
+            .curves
+            .push(curve(conic(orbit), [0x60, 0xc0, 0xe0], 1));
+    }
+    if let Some(orbit) = &arrival {
+        scene
+            .curves
+            .push(curve(conic(orbit), [0xe0, 0x70, 0xc0], 1));
+    }
+    if let Some(orbit) = &transfer {
+        scene
+            .curves
+            .push(curve(conic(orbit), [0x70, 0x70, 0x70], 1));
+        // transfer arc towards increasing true anomaly, r2 projected into the plane
+        let (i_p, i_q) = orbit.perifocal_basis();
+        let nu2 = (r2 * i_q).atan2(r2 * i_p);
+        let nu2 = match Conic::from_ecc(orbit.ecc) {
+            Conic::Elliptic => orbit.nu + (nu2 - orbit.nu).rem_euclid(2.0 * PI),
+            _ => nu2,
+        };
+        let arc = orbit_points(orbit, orbit.nu, nu2);
+        scene.curves.push(curve(arc, [0xf0, 0xa0, 0x20], 2));
+    }
+    scene
+}
+
+fn orbit_points(orbit: &Orbit, nu1: f64, nu2: f64) -> Vec<Vector3D> {
+    (0..=ORBIT_SAMPLES)
+        .map(|k| orbit.position_at(nu1 + (nu2 - nu1) * k as f64 / ORBIT_SAMPLES as f64))
+        .collect()
+}
+
+#[cfg(test)]
+#[test]
+fn test_render() {
+    let scene = Scene::new(1.0, 2.0);
+    let camera = Camera {
+        yaw: 0.0,
+        pitch: 0.0,
+        zoom: 1.0,
+    };
+    let image = scene.render(&camera, 101, 81);
+    assert_eq!(image.pixels.len(), 101 * 81 * 3);
+    // origin in the middle, +Y to the right and +Z up seen from +X
+    let (x, y) = scene
+        .project(&camera, 101, 81, Vector3D::default())
+        .unwrap();
+    assert!((x - 50.5).abs() < 1e-9 && (y - 40.5).abs() < 1e-9);
+    let [_, y_tip, z_tip] = scene.axis_tips();
+    assert!(scene.project(&camera, 101, 81, y_tip).unwrap().0 > 60.0);
+    assert!(scene.project(&camera, 101, 81, z_tip).unwrap().1 < 30.0);
+    assert_eq!(
+        scene.project(&camera, 101, 81, Vector3D::new(9.0, 0.0, 0.0)),
+        None
+    );
+
+    // the body hides the X axis that points at the camera, the Z axis is drawn above it
+    let center = image.get(50, 40);
+    assert!(center != BACKGROUND && center[2] > center[0]);
+    assert_eq!(image.get(50, 20), [0x50, 0x70, 0xf0]);
+    assert_eq!(image.get(0, 0), BACKGROUND);
+
+    let mut camera = camera;
+    camera.rotate(-0.5, 10.0);
+    assert!(camera.pitch < PI / 2.0);
+    assert!((camera.yaw - (2.0 * PI - 0.5)).abs() < 1e-12);
+}
+
+#[test]
+fn test_png() {
+    let mut image = Image::new(3, 2);
+    image.set(2, 1, [1, 2, 3]);
+    let mut png = Vec::new();
+    image.write_png(&mut png).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+    // IEND chunk with its well-known CRC
+    assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+    // one stored block of two filtered rows of three pixels
+    let idat = 8 + 25;
+    assert_eq!(&png[idat + 4..idat + 8], b"IDAT");
+    let data = &png[idat + 8..idat + 8 + 2 + 5 + 20 + 4];
+    assert_eq!(&data[..7], &[0x78, 0x01, 1, 20, 0, !20, 0xff]);
+    assert_eq!(&data[7 + 17..7 + 20], &[1, 2, 3]);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    assert_eq!(crc32(b"123456789".iter()), 0xcbf4_3926);
+}
+
+#[test]
+fn test_transfer_scene() {
+    let mu = 3.986004e5;
+    let r1 = Vector3D::new(7000.0, 0.0, 0.0);
+    let r2 = Vector3D::new(0.0, 8000.0, 3000.0);
+    let mut ls = LambertSolver::new(r1, r2, 3000.0, mu).unwrap();
+    ls.body_radius = 6378.0;
+    // axes, r1, r2, transfer conic and arc
+    let scene = transfer_scene(&ls);
+    assert_eq!(scene.curves.len(), 7);
+    let arc = &scene.curves[6].points;
+    assert!((arc[0] - r1).mag() < 1e-6);
+    assert!((arc[arc.len() - 1] - r2).mag() < 1e-6);
+    assert!(scene.extent >= r2.mag());
+
+    ls.v_dep = Vector3D::new(0.0, (mu / 7000.0).sqrt(), 0.0);
+    let scene = transfer_scene(&ls);
+    assert_eq!(scene.curves.len(), 8);
+    assert!(scene.curves[5]
+        .points
+        .iter()
+        .all(|p| (p.mag() - 7000.0).abs() < 1e-6));
+    assert!(scene.render(&Camera::default(), 64, 64).pixels.len() == 64 * 64 * 3);
+}
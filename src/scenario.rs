@@ -0,0 +1,252 @@
+// Scenario files, the Lambert problem and central body saved as JSON or TOML, and
+// the list of recently used scenario files
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::LambertSolver;
+
+// number of files kept in File > Open Recent
+pub const MAX_RECENT_FILES: usize = 10;
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub lambert_problem: LambertSolver,
+
+    // name of the catalogue body that set mu and the body radius
+    #[serde(default)]
+    pub central_body: String,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    TomlSerialize(toml::ser::Error),
+    TomlDeserialize(toml::de::Error),
+    // scenario files must have a .json or .toml extension
+    UnknownFormat(PathBuf),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::Json(e) => e.fmt(f),
+            Self::TomlSerialize(e) => e.fmt(f),
+            Self::TomlDeserialize(e) => e.fmt(f),
+            Self::UnknownFormat(path) => write!(
+                f,
+                "unknown scenario format of {}, expected .json or .toml",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl Error for ScenarioError {}
+
+impl From<io::Error> for ScenarioError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<toml::ser::Error> for ScenarioError {
+    fn from(e: toml::ser::Error) -> Self {
+        Self::TomlSerialize(e)
+    }
+}
+
+impl From<toml::de::Error> for ScenarioError {
+    fn from(e: toml::de::Error) -> Self {
+        Self::TomlDeserialize(e)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ScenarioFormat {
+    Json,
+    Toml,
+}
+
+impl ScenarioFormat {
+    fn from_path(path: &Path) -> Result<Self, ScenarioError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(ScenarioError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+}
+
+impl Scenario {
+    pub fn save(&self, path: &Path) -> Result<(), ScenarioError> {
+        // the solution is not saved, only the problem definition
+        let contents = match ScenarioFormat::from_path(path)? {
+            ScenarioFormat::Json => serde_json::to_string_pretty(self)?,
+            ScenarioFormat::Toml => toml::to_string(self)?,
+        };
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ScenarioError> {
+        let format = ScenarioFormat::from_path(path)?;
+        let contents = fs::read_to_string(path)?;
+        let mut scenario: Self = match format {
+            ScenarioFormat::Json => serde_json::from_str(&contents)?,
+            ScenarioFormat::Toml => toml::from_str(&contents)?,
+        };
+        scenario.lambert_problem.recalculate_solution();
+        Ok(scenario)
+    }
+}
+
+pub fn add_recent_file(recent_files: &[PathBuf], path: &Path) -> Vec<PathBuf> {
+    // most recent first, without duplicates
+    let mut files = vec![path.to_path_buf()];
+    files.extend(recent_files.iter().filter(|&p| p != path).cloned());
+    files.truncate(MAX_RECENT_FILES);
+    files
+}
+
+pub fn read_recent_files(path: &Path) -> Vec<PathBuf> {
+    // a missing or unreadable list is treated as empty
+    match fs::read_to_string(path) {
+        Ok(contents) => contents.lines().map(PathBuf::from).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn write_recent_files(path: &Path, recent_files: &[PathBuf]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let contents: Vec<_> = recent_files
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    fs::write(path, contents.join("\n"))
+}
+
+pub fn recent_files_path() -> Option<PathBuf> {
+    // $XDG_CONFIG_HOME/orbital/recent_files, falling back to ~/.config
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_dir.join("orbital").join("recent_files"))
+}
+
+#[cfg(test)]
+fn test_dir(name: &str) -> PathBuf {
+    // fresh directory under the system temp dir
+    let dir = env::temp_dir().join(format!("orbital-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_round_trip() {
+    let dir = test_dir("round-trip");
+    let mut scenario = Scenario {
+        lambert_problem: LambertSolver::new(
+            crate::Vector3D::new(5000.0, 10000.0, 2100.0),
+            crate::Vector3D::new(-14600.0, 2500.0, 7000.0),
+            3600.0,
+            crate::bodies::EARTH.mu,
+        )
+        .unwrap(),
+        ..Default::default()
+    };
+    scenario.central_body = crate::bodies::EARTH.name.to_string();
+
+    for name in &["scenario.json", "scenario.toml"] {
+        let path = dir.join(name);
+        scenario.save(&path).unwrap();
+        let loaded = Scenario::load(&path).unwrap();
+        assert_eq!(loaded.central_body, "Earth");
+        let (problem, expected) = (&loaded.lambert_problem, &scenario.lambert_problem);
+        assert_eq!(problem.get_r1(), expected.get_r1());
+        assert_eq!(problem.get_r2(), expected.get_r2());
+        assert_eq!(problem.get_tof(), expected.get_tof());
+        assert_eq!(problem.get_mu(), expected.get_mu());
+        assert_eq!(problem.get_options(), expected.get_options());
+        // the solution is recalculated on load
+        assert_eq!(problem.get_solution(), expected.get_solution());
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_scenario_errors() {
+    let dir = test_dir("errors");
+    let scenario = Scenario::default();
+    let path = dir.join("scenario.txt");
+    assert!(matches!(
+        scenario.save(&path),
+        Err(ScenarioError::UnknownFormat(p)) if p == path
+    ));
+    fs::write(&path, "{}").unwrap();
+    assert!(matches!(
+        Scenario::load(&path),
+        Err(ScenarioError::UnknownFormat(_))
+    ));
+
+    let path = dir.join("malformed.json");
+    fs::write(&path, "{\"lambert_problem\": ").unwrap();
+    assert!(matches!(Scenario::load(&path), Err(ScenarioError::Json(_))));
+    let path = dir.join("malformed.toml");
+    fs::write(&path, "lambert_problem = [").unwrap();
+    assert!(matches!(
+        Scenario::load(&path),
+        Err(ScenarioError::TomlDeserialize(_))
+    ));
+    assert!(matches!(
+        Scenario::load(&dir.join("missing.json")),
+        Err(ScenarioError::Io(_))
+    ));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_recent_files() {
+    let files: Vec<PathBuf> = (0..MAX_RECENT_FILES)
+        .map(|i| PathBuf::from(format!("{}.json", i)))
+        .collect();
+
+    // reopening moves a file to the front instead of adding it twice
+    let recent = add_recent_file(&files, Path::new("3.json"));
+    assert_eq!(recent.len(), MAX_RECENT_FILES);
+    assert_eq!(recent[0], Path::new("3.json"));
+    assert_eq!(
+        recent.iter().filter(|p| *p == Path::new("3.json")).count(),
+        1
+    );
+    assert_eq!(recent[1..4], files[..3]);
+
+    // the oldest file is dropped beyond the cap
+    let recent = add_recent_file(&files, Path::new("new.json"));
+    assert_eq!(recent.len(), MAX_RECENT_FILES);
+    assert_eq!(recent[0], Path::new("new.json"));
+    assert_eq!(recent[1..], files[..MAX_RECENT_FILES - 1]);
+
+    let dir = test_dir("recent-files");
+    let path = dir.join("orbital").join("recent_files");
+    assert!(read_recent_files(&path).is_empty());
+    write_recent_files(&path, &recent).unwrap();
+    assert_eq!(read_recent_files(&path), recent);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,581 @@
+// Epochs and durations in the UTC, TAI, TT and TDB time scales.
+// An epoch is stored as TT seconds past J2000 (2000-01-01T12:00:00 TT). UTC follows
+// the IERS leap second table from 1972, earlier dates use TAI - UTC = 10 s. TDB uses
+// the two-term periodic series of the Astronomical Almanac, good to about 30 us.
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// Julian date of J2000.0
+pub const J2000: f64 = 2451545.0;
+// Julian date of the modified Julian date epoch, 1858-11-17T00:00:00
+pub const MJD_OFFSET: f64 = 2400000.5;
+pub const SECONDS_PER_DAY: f64 = 86400.0;
+
+// TT - TAI (s)
+const TT_TAI: f64 = 32.184;
+
+// (year, month) from the first day of which TAI - UTC has the given number of seconds
+const LEAP_SECONDS: [(i32, u32, f64); 28] = [
+    (1972, 1, 10.0),
+    (1972, 7, 11.0),
+    (1973, 1, 12.0),
+    (1974, 1, 13.0),
+    (1975, 1, 14.0),
+    (1976, 1, 15.0),
+    (1977, 1, 16.0),
+    (1978, 1, 17.0),
+    (1979, 1, 18.0),
+    (1980, 1, 19.0),
+    (1981, 7, 20.0),
+    (1982, 7, 21.0),
+    (1983, 7, 22.0),
+    (1985, 7, 23.0),
+    (1988, 1, 24.0),
+    (1990, 1, 25.0),
+    (1991, 1, 26.0),
+    (1992, 7, 27.0),
+    (1993, 7, 28.0),
+    (1994, 7, 29.0),
+    (1996, 1, 30.0),
+    (1997, 7, 31.0),
+    (1999, 1, 32.0),
+    (2006, 1, 33.0),
+    (2009, 1, 34.0),
+    (2012, 7, 35.0),
+    (2015, 7, 36.0),
+    (2017, 1, 37.0),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TimeScale {
+    Utc,
+    Tai,
+    Tt,
+    Tdb,
+}
+
+impl fmt::Display for TimeScale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Utc => "UTC",
+            Self::Tai => "TAI",
+            Self::Tt => "TT",
+            Self::Tdb => "TDB",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for TimeScale {
+    type Err = EpochParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "UTC" => Ok(Self::Utc),
+            "TAI" => Ok(Self::Tai),
+            "TT" => Ok(Self::Tt),
+            "TDB" => Ok(Self::Tdb),
+            _ => Err(EpochParseError::UnknownTimeScale(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EpochParseError {
+    // not of the form YYYY-MM-DD[THH:MM[:SS[.sss]]][Z][ SCALE]
+    InvalidFormat(String),
+    // calendar field out of range, e.g. month 13 or 23:59:60 on a day without a leap second
+    OutOfRange { field: &'static str, value: f64 },
+    UnknownTimeScale(String),
+}
+
+impl fmt::Display for EpochParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidFormat(s) => write!(
+                f,
+                "invalid epoch '{}', expected YYYY-MM-DDTHH:MM:SS[Z] [UTC|TAI|TT|TDB]",
+                s
+            ),
+            Self::OutOfRange { field, value } => write!(f, "{} {} out of range", field, value),
+            Self::UnknownTimeScale(s) => {
+                write!(
+                    f,
+                    "unknown time scale '{}', expected UTC, TAI, TT or TDB",
+                    s
+                )
+            }
+        }
+    }
+}
+
+impl Error for EpochParseError {}
+
+// Signed length of time in SI seconds
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Duration {
+    seconds: f64,
+}
+
+impl Duration {
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self { seconds }
+    }
+
+    pub fn from_days(days: f64) -> Self {
+        Self::from_seconds(days * SECONDS_PER_DAY)
+    }
+
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+
+    pub fn days(&self) -> f64 {
+        self.seconds / SECONDS_PER_DAY
+    }
+}
+
+impl Add for Duration {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_seconds(self.seconds + rhs.seconds)
+    }
+}
+
+impl Sub for Duration {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_seconds(self.seconds - rhs.seconds)
+    }
+}
+
+impl Neg for Duration {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_seconds(-self.seconds)
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} s ({:.6} days)", self.seconds, self.days())
+    }
+}
+
+// Calendar date and time of day in some time scale, second is 60.x during a leap second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Epoch {
+    // TT seconds past J2000
+    tt: f64,
+}
+
+impl Epoch {
+    pub fn j2000() -> Self {
+        Self { tt: 0.0 }
+    }
+
+    pub fn from_seconds(seconds: f64, scale: TimeScale) -> Self {
+        // seconds past 2000-01-01T12:00:00 of the time scale, without leap seconds for UTC
+        let tt = match scale {
+            TimeScale::Utc => seconds + leap_seconds(seconds) + TT_TAI,
+            TimeScale::Tai => seconds + TT_TAI,
+            TimeScale::Tt => seconds,
+            // the periodic term changes by less than 1e-9 s between TT and TDB
+            TimeScale::Tdb => seconds - tdb_tt(seconds),
+        };
+        Self { tt }
+    }
+
+    pub fn seconds(&self, scale: TimeScale) -> f64 {
+        // inverse of from_seconds, the leap second itself repeats the first UTC second
+        // of the next day
+        match scale {
+            TimeScale::Utc => {
+                let tai = self.tt - TT_TAI;
+                tai - utc_offset(tai).0
+            }
+            TimeScale::Tai => self.tt - TT_TAI,
+            TimeScale::Tt => self.tt,
+            TimeScale::Tdb => self.tt + tdb_tt(self.tt),
+        }
+    }
+
+    pub fn from_jd(jd: f64, scale: TimeScale) -> Self {
+        Self::from_seconds((jd - J2000) * SECONDS_PER_DAY, scale)
+    }
+
+    pub fn jd(&self, scale: TimeScale) -> f64 {
+        J2000 + self.seconds(scale) / SECONDS_PER_DAY
+    }
+
+    pub fn from_mjd(mjd: f64, scale: TimeScale) -> Self {
+        Self::from_jd(mjd + MJD_OFFSET, scale)
+    }
+
+    pub fn mjd(&self, scale: TimeScale) -> f64 {
+        self.jd(scale) - MJD_OFFSET
+    }
+
+    pub fn from_datetime(dt: &DateTime, scale: TimeScale) -> Result<Self, EpochParseError> {
+        // Gregorian calendar, second 60 is only valid on UTC days ending in a leap second
+        let out_of_range = |field, value| Err(EpochParseError::OutOfRange { field, value });
+        if !(1..=12).contains(&dt.month) {
+            return out_of_range("month", f64::from(dt.month));
+        }
+        if dt.day < 1 || dt.day > days_in_month(dt.year, dt.month) {
+            return out_of_range("day", f64::from(dt.day));
+        }
+        if dt.hour > 23 {
+            return out_of_range("hour", f64::from(dt.hour));
+        }
+        if dt.minute > 59 {
+            return out_of_range("minute", f64::from(dt.minute));
+        }
+        let day_start = day_seconds(dt.year, dt.month, dt.day);
+        let max_second = if scale == TimeScale::Utc && dt.hour == 23 && dt.minute == 59 {
+            60.0 + leap_seconds(day_start + SECONDS_PER_DAY) - leap_seconds(day_start)
+        } else {
+            60.0
+        };
+        if !(0.0..max_second).contains(&dt.second) {
+            return out_of_range("second", dt.second);
+        }
+
+        let time_of_day = f64::from(dt.hour * 3600 + dt.minute * 60) + dt.second;
+        Ok(match scale {
+            // the offset at the start of the day, so that 23:59:60 falls in the leap second
+            TimeScale::Utc => Self {
+                tt: day_start + time_of_day + leap_seconds(day_start) + TT_TAI,
+            },
+            _ => Self::from_seconds(day_start + time_of_day, scale),
+        })
+    }
+
+    pub fn datetime(&self, scale: TimeScale) -> DateTime {
+        match scale {
+            TimeScale::Utc => {
+                let tai = self.tt - TT_TAI;
+                let (offset, leap) = utc_offset(tai);
+                if leap {
+                    // 23:59:60.x is one second past 23:59:59.x
+                    let mut dt = datetime_from_seconds(tai - offset - 1.0);
+                    dt.second += 1.0;
+                    dt
+                } else {
+                    datetime_from_seconds(tai - offset)
+                }
+            }
+            _ => datetime_from_seconds(self.seconds(scale)),
+        }
+    }
+
+    pub fn to_iso(&self, scale: TimeScale) -> String {
+        // ISO-8601 with milliseconds, UTC ends in Z and the other scales in their name
+        let seconds = |s: f64| (s * 1000.0).round() / 1000.0;
+        let rounded = Self {
+            tt: seconds(self.tt),
+        };
+        let dt = match scale {
+            TimeScale::Tdb => datetime_from_seconds(seconds(self.seconds(scale))),
+            _ => rounded.datetime(scale),
+        };
+        let suffix = match scale {
+            TimeScale::Utc => "Z".to_string(),
+            _ => format!(" {}", scale),
+        };
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:06.3}{}",
+            dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second, suffix
+        )
+    }
+}
+
+impl FromStr for Epoch {
+    type Err = EpochParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // YYYY-MM-DD[THH:MM[:SS[.sss]]][Z][ SCALE], UTC unless a time scale is given
+        let invalid = || EpochParseError::InvalidFormat(s.to_string());
+        let mut parts = s.split_whitespace();
+        let (iso, scale) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(iso), None, _) => (iso, None),
+            (Some(iso), Some(scale), None) => (iso, Some(scale.parse::<TimeScale>()?)),
+            _ => return Err(invalid()),
+        };
+        let (iso, zulu) = match iso.strip_suffix('Z') {
+            Some(iso) => (iso, true),
+            None => (iso, false),
+        };
+        let scale = match (scale, zulu) {
+            (Some(scale), true) if scale != TimeScale::Utc => return Err(invalid()),
+            (Some(scale), _) => scale,
+            (None, _) => TimeScale::Utc,
+        };
+
+        let (date, time) = match iso.find('T') {
+            Some(i) => (&iso[..i], Some(&iso[i + 1..])),
+            None => (iso, None),
+        };
+        // a leading minus sign belongs to the year
+        let (sign, date) = match date.strip_prefix('-') {
+            Some(date) => (-1, date),
+            None => (1, date),
+        };
+        let date: Vec<_> = date.split('-').collect();
+        let (year, month, day) = match date[..] {
+            [year, month, day] if month.len() == 2 && day.len() == 2 => (
+                year.parse::<i32>().map_err(|_| invalid())? * sign,
+                month.parse().map_err(|_| invalid())?,
+                day.parse().map_err(|_| invalid())?,
+            ),
+            _ => return Err(invalid()),
+        };
+        let (hour, minute, second) = match time.map(|t| t.split(':').collect::<Vec<_>>()) {
+            None => (0, 0, 0.0),
+            Some(time) => match time[..] {
+                [hour, minute] => (
+                    hour.parse().map_err(|_| invalid())?,
+                    minute.parse().map_err(|_| invalid())?,
+                    0.0,
+                ),
+                [hour, minute, second] => (
+                    hour.parse().map_err(|_| invalid())?,
+                    minute.parse().map_err(|_| invalid())?,
+                    second.parse().map_err(|_| invalid())?,
+                ),
+                _ => return Err(invalid()),
+            },
+        };
+
+        Self::from_datetime(
+            &DateTime {
+                year,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+            },
+            scale,
+        )
+    }
+}
+
+impl fmt::Display for Epoch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_iso(TimeScale::Utc))
+    }
+}
+
+impl Add<Duration> for Epoch {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self {
+        Self {
+            tt: self.tt + rhs.seconds,
+        }
+    }
+}
+
+impl Sub<Duration> for Epoch {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self {
+        Self {
+            tt: self.tt - rhs.seconds,
+        }
+    }
+}
+
+impl Sub for Epoch {
+    // elapsed time, leap seconds included
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Duration {
+        Duration::from_seconds(self.tt - rhs.tt)
+    }
+}
+
+fn tdb_tt(tt: f64) -> f64 {
+    // TDB - TT (s), g is the mean anomaly of the Earth
+    let g = (357.53 + 0.98560028 * tt / SECONDS_PER_DAY).to_radians();
+    0.001657 * g.sin() + 0.00001385 * (2.0 * g).sin()
+}
+
+fn leap_seconds(utc: f64) -> f64 {
+    // TAI - UTC at UTC seconds past J2000
+    LEAP_SECONDS
+        .iter()
+        .rev()
+        .find(|&&(year, month, _)| day_seconds(year, month, 1) <= utc)
+        .map_or(LEAP_SECONDS[0].2, |&(_, _, offset)| offset)
+}
+
+fn utc_offset(tai: f64) -> (f64, bool) {
+    // TAI - UTC at TAI seconds past J2000, and whether the epoch is in a leap second
+    let start = |&(year, month, offset): &(i32, u32, f64)| day_seconds(year, month, 1) + offset;
+    match LEAP_SECONDS.iter().rposition(|entry| start(entry) <= tai) {
+        Some(i) => {
+            let leap = LEAP_SECONDS
+                .get(i + 1)
+                .is_some_and(|next| tai >= start(next) - 1.0);
+            (LEAP_SECONDS[i].2, leap)
+        }
+        None => (LEAP_SECONDS[0].2, false),
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn day_number(year: i32, month: u32, day: u32) -> i64 {
+    // Julian day number of a Gregorian date (Fliegel and Van Flandern)
+    let (y, m, d) = (i64::from(year), i64::from(month), i64::from(day));
+    let a = (m - 14) / 12;
+    (1461 * (y + 4800 + a)) / 4 + (367 * (m - 2 - 12 * a)) / 12 - (3 * ((y + 4900 + a) / 100)) / 4
+        + d
+        - 32075
+}
+
+fn day_seconds(year: i32, month: u32, day: u32) -> f64 {
+    // seconds from J2000 to the start of the day
+    (day_number(year, month, day) - 2451545) as f64 * SECONDS_PER_DAY - SECONDS_PER_DAY / 2.0
+}
+
+fn datetime_from_seconds(seconds: f64) -> DateTime {
+    let days = ((seconds + SECONDS_PER_DAY / 2.0) / SECONDS_PER_DAY).floor();
+    let time_of_day = seconds + SECONDS_PER_DAY / 2.0 - days * SECONDS_PER_DAY;
+
+    // Gregorian date of the Julian day number
+    let l = days as i64 + 2451545 + 68569;
+    let n = 4 * l / 146097;
+    let l = l - (146097 * n + 3) / 4;
+    let i = 4000 * (l + 1) / 1461001;
+    let l = l - 1461 * i / 4 + 31;
+    let j = 80 * l / 2447;
+    let day = l - 2447 * j / 80;
+    let l = j / 11;
+    let month = j + 2 - 12 * l;
+    let year = 100 * (n - 49) + i + l;
+
+    let minutes = (time_of_day / 60.0).floor();
+    DateTime {
+        year: year as i32,
+        month: month as u32,
+        day: day as u32,
+        hour: (minutes / 60.0) as u32,
+        minute: (minutes % 60.0) as u32,
+        second: time_of_day - minutes * 60.0,
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_julian_dates() {
+    let j2000: Epoch = "2000-01-01T12:00:00 TT".parse().unwrap();
+    assert_eq!(j2000, Epoch::j2000());
+    assert_eq!(j2000.jd(TimeScale::Tt), J2000);
+    assert_eq!(j2000.mjd(TimeScale::Tt), 51544.5);
+    assert_eq!(Epoch::from_mjd(51544.5, TimeScale::Tt), j2000);
+    // TT - UTC was 64.184 s in 2000
+    assert_eq!(j2000.to_iso(TimeScale::Utc), "2000-01-01T11:58:55.816Z");
+    assert_eq!(j2000.to_iso(TimeScale::Tai), "2000-01-01T11:59:27.816 TAI");
+    assert!((j2000.seconds(TimeScale::Tdb) + 7.3e-5).abs() < 1e-6);
+
+    // Sputnik 1
+    let sputnik: Epoch = "1957-10-04T19:28:34Z".parse().unwrap();
+    assert!((sputnik.jd(TimeScale::Utc) - 2436116.31150).abs() < 1e-5);
+    let dt = Epoch::from_jd(2436116.31150463, TimeScale::Utc).datetime(TimeScale::Utc);
+    assert_eq!(
+        (dt.year, dt.month, dt.day, dt.hour, dt.minute),
+        (1957, 10, 4, 19, 28)
+    );
+    assert!((dt.second - 34.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_leap_seconds() {
+    let before: Epoch = "2016-12-31T23:59:59.5Z".parse().unwrap();
+    let leap: Epoch = "2016-12-31T23:59:60.5Z".parse().unwrap();
+    let after: Epoch = "2017-01-01T00:00:00.5Z".parse().unwrap();
+    assert_eq!((leap - before).seconds(), 1.0);
+    assert_eq!((after - leap).seconds(), 1.0);
+    assert_eq!(leap.to_string(), "2016-12-31T23:59:60.500Z");
+    assert_eq!(after.to_iso(TimeScale::Tai), "2017-01-01T00:00:37.500 TAI");
+    assert_eq!(before.to_iso(TimeScale::Tai), "2017-01-01T00:00:35.500 TAI");
+    assert_eq!(
+        "2017-12-31T23:59:60Z".parse::<Epoch>(),
+        Err(EpochParseError::OutOfRange {
+            field: "second",
+            value: 60.0
+        })
+    );
+
+    // 2016 has one more second than the calendar suggests
+    let year = "2017-01-01Z".parse::<Epoch>().unwrap() - "2016-01-01".parse::<Epoch>().unwrap();
+    assert_eq!(year.seconds(), 366.0 * SECONDS_PER_DAY + 1.0);
+    assert_eq!(year.days(), 366.0 + 1.0 / SECONDS_PER_DAY);
+}
+
+#[test]
+fn test_parse_and_format() {
+    let epoch: Epoch = "2020-07-30T11:50".parse().unwrap();
+    assert_eq!(epoch.to_string(), "2020-07-30T11:50:00.000Z");
+    assert_eq!(
+        (epoch + Duration::from_days(203.0)).to_string(),
+        "2021-02-18T11:50:00.000Z"
+    );
+    assert_eq!(
+        epoch - Duration::from_seconds(0.25) + Duration::from_seconds(0.25),
+        epoch
+    );
+    let tdb: Epoch = "2020-07-30T11:51:09.184 TDB".parse().unwrap();
+    assert!((tdb - epoch).seconds().abs() < 2e-3);
+    assert_eq!(tdb.to_iso(TimeScale::Tdb), "2020-07-30T11:51:09.184 TDB");
+    assert_eq!(
+        "-4713-11-24T12:00 TT"
+            .parse::<Epoch>()
+            .unwrap()
+            .jd(TimeScale::Tt),
+        0.0
+    );
+
+    for s in &[
+        "2020-07-30T",
+        "2020-7-30",
+        "2020-07-30T11",
+        "2020-07-30T11:50Z TT",
+        "2020-07-30 TCB",
+        "2020-02-30",
+        "2020-07-30T24:00",
+    ] {
+        assert!(s.parse::<Epoch>().is_err(), "{}", s);
+    }
+}
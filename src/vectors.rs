@@ -2,9 +2,14 @@
 use std::fmt;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
+#[cfg(feature = "gui")]
 use druid::{Data, Lens};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Data, Lens)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "gui", derive(Data, Lens))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vector3D {
     pub x: f64,
     pub y: f64,
@@ -1,14 +1,947 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+use std::thread;
+
 use druid::{
-    widget::{Button, Flex, Label, TextBox},
-    Env, Lens, LensExt, Widget, WidgetExt,
+    commands,
+    kurbo::{BezPath, Circle, Line},
+    lens,
+    piet::{ImageFormat, InterpolationMode, Text, TextLayout, TextLayoutBuilder},
+    platform_menus, theme,
+    widget::{Button, Controller, Flex, Label, Radio, TextBox},
+    BoxConstraints, Color, ContextMenu, Data, Env, Event, EventCtx, LayoutCtx, Lens, LensExt,
+    LifeCycle, LifeCycleCtx, LocalizedString, MenuDesc, MenuItem, PaintCtx, Point, Rect,
+    RenderContext, Selector, Size, SysMods, Target, UpdateCtx, Vec2, Widget, WidgetExt, WindowDesc,
 };
 
-use super::data::FloatFormatter;
-use crate::lambert::LambertSolverStatus;
+use super::data::{AppState, FloatFormatter, PorkchopState};
+use super::delegate::{
+    export_dialog_options, open_dialog_options, save_dialog_options, OPEN_RECENT,
+    PORKCHOP_COMPUTED, SELECT_BODY,
+};
+use crate::bodies::BODIES;
+use crate::kepler::Conic;
+use crate::lambert::{LambertBranch, LambertOptions, LambertSolverStatus, TransferDirection};
+use crate::porkchop::{Porkchop, PorkchopNode, PorkchopTransfer};
+use crate::render::{self, Camera};
+use crate::time::{Epoch, TimeScale};
 use crate::LambertSolver;
+use crate::Orbit;
 use crate::Vector3D;
 
-pub fn build_ui() -> impl Widget<LambertSolver> {
+const PORKCHOP_PLOT_SIZE: Size = Size::new(640.0, 480.0);
+// left, top, right and bottom space around the cells for the axis labels
+const PORKCHOP_PLOT_MARGINS: (f64, f64, f64, f64) = (90.0, 10.0, 10.0, 60.0);
+const ORBIT_PLOT_SIZE: Size = Size::new(420.0, 420.0);
+// points along the full conic and along the transfer arc
+const ORBIT_PLOT_SAMPLES: usize = 360;
+const ORBIT_VIEW_SIZE: Size = Size::new(600.0, 600.0);
+
+pub fn build_ui() -> impl Widget<AppState> {
+    let porkchop_button =
+        Button::new("Porkchop plot...").on_click(|ctx, _data: &mut AppState, _env| {
+            ctx.new_window(WindowDesc::new(build_porkchop_ui).title("Porkchop plot"));
+        });
+    let orbit_view_button =
+        Button::new("3D view...").on_click(|ctx, _data: &mut AppState, _env| {
+            ctx.new_window(WindowDesc::new(build_orbit_view_ui).title("3D orbit view"));
+        });
+
+    Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(body_input_scope())
+                .with_default_spacer()
+                .with_child(porkchop_button)
+                .with_default_spacer()
+                .with_child(orbit_view_button),
+        )
+        .with_default_spacer()
+        .with_child(
+            lambert_scope()
+                .lens(AppState::lambert_problem)
+                .controller(CustomBody),
+        )
+        .with_default_spacer()
+        .with_child(file_label())
+        .center()
+}
+
+pub fn build_menu(data: &AppState) -> MenuDesc<AppState> {
+    let menu = MenuDesc::empty();
+    #[cfg(target_os = "macos")]
+    let menu = menu.append(platform_menus::mac::application::default());
+    menu.append(file_menu(data))
+}
+
+fn file_menu(data: &AppState) -> MenuDesc<AppState> {
+    let open = MenuItem::new(
+        LocalizedString::new("orbital-menu-file-open").with_placeholder("Open..."),
+        commands::SHOW_OPEN_PANEL.with(open_dialog_options()),
+    )
+    .hotkey(SysMods::Cmd, "o");
+    let save = MenuItem::new(
+        LocalizedString::new("orbital-menu-file-save").with_placeholder("Save"),
+        commands::SAVE_FILE,
+    )
+    .hotkey(SysMods::Cmd, "s");
+    let save_as = MenuItem::new(
+        LocalizedString::new("orbital-menu-file-save-as").with_placeholder("Save As..."),
+        commands::SHOW_SAVE_PANEL.with(save_dialog_options()),
+    )
+    .hotkey(SysMods::CmdShift, "S");
+    let export = MenuItem::new(
+        LocalizedString::new("orbital-menu-file-export-png").with_placeholder("Export PNG..."),
+        commands::SHOW_SAVE_PANEL.with(export_dialog_options()),
+    )
+    .hotkey(SysMods::Cmd, "e");
+
+    MenuDesc::new(LocalizedString::new("common-menu-file-menu").with_placeholder("File"))
+        .append(open)
+        .append(recent_files_menu(data))
+        .append_separator()
+        .append(save)
+        .append(save_as)
+        .append(export)
+        .append_separator()
+        .append(platform_menus::win::file::exit())
+}
+
+fn recent_files_menu(data: &AppState) -> MenuDesc<AppState> {
+    let menu = MenuDesc::new(
+        LocalizedString::new("orbital-menu-file-open-recent").with_placeholder("Open Recent"),
+    );
+    if data.recent_files.is_empty() {
+        return menu.append(
+            MenuItem::new(
+                LocalizedString::new("orbital-menu-no-recent-files")
+                    .with_placeholder("No recent files"),
+                Selector::NOOP,
+            )
+            .disabled(),
+        );
+    }
+    menu.append_iter(|| {
+        data.recent_files.iter().map(|path| {
+            MenuItem::new(
+                LocalizedString::new("orbital-menu-recent-file")
+                    .with_placeholder(path.display().to_string()),
+                OPEN_RECENT.with(path.clone()),
+            )
+        })
+    })
+}
+
+fn body_input_scope() -> impl Widget<AppState> {
+    let button = Button::new(|data: &AppState, _env: &Env| match data.selected_body() {
+        Some(body) => body.name.to_string(),
+        None => "custom".to_string(),
+    })
+    .controller(BodyMenu);
+
+    Flex::row()
+        .cross_axis_alignment(druid::widget::CrossAxisAlignment::Baseline)
+        .with_child(Label::new("central body: "))
+        .with_child(button)
+}
+
+// Opens the central body catalogue as a drop-down menu below the pointer
+struct BodyMenu;
+
+impl<W: Widget<AppState>> Controller<AppState, W> for BodyMenu {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                let menu = MenuDesc::<AppState>::empty().append_iter(|| {
+                    BODIES.iter().map(|body| {
+                        MenuItem::new(
+                            LocalizedString::new("orbital-menu-body").with_placeholder(body.name),
+                            SELECT_BODY.with(body),
+                        )
+                    })
+                });
+                ctx.show_context_menu(ContextMenu::new(menu, mouse.window_pos));
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+// Leaves the catalogue body once mu or the body radius is edited by hand
+struct CustomBody;
+
+impl<W: Widget<AppState>> Controller<AppState, W> for CustomBody {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppState,
+        env: &Env,
+    ) {
+        let mu = data.lambert_problem.get_mu();
+        let radius = data.lambert_problem.body_radius;
+        child.event(ctx, event, data, env);
+        if data.lambert_problem.get_mu() != mu || data.lambert_problem.body_radius != radius {
+            data.clear_body();
+        }
+    }
+}
+
+// Solves the Lambert problem again whenever r1, r2, the time-of-flight, mu or the
+// options are edited, so the plot and the output labels show the same solution
+struct AutoSolve;
+
+impl<W: Widget<LambertSolver>> Controller<LambertSolver, W> for AutoSolve {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut LambertSolver,
+        env: &Env,
+    ) {
+        let inputs = |ls: &LambertSolver| {
+            (
+                ls.get_r1(),
+                ls.get_r2(),
+                ls.get_tof(),
+                ls.get_mu(),
+                ls.get_options(),
+            )
+        };
+        let old_inputs = inputs(data);
+        child.event(ctx, event, data, env);
+        if inputs(data) != old_inputs {
+            data.recalculate_solution();
+        }
+    }
+}
+
+pub fn build_porkchop_ui() -> impl Widget<AppState> {
+    let compute_button = Button::new("Compute").on_click(|ctx, data: &mut AppState, _env| {
+        // same transfer direction and solver settings as the Lambert panel
+        let options = data.lambert_problem.get_options();
+        if let Some(sweep) = data.porkchop.start_compute(&options) {
+            // keeps the UI responsive during large sweeps, the grid is posted back when done
+            let sink = ctx.get_external_handle();
+            thread::spawn(move || {
+                let plot = Arc::new(sweep.run());
+                // fails only once the application has quit
+                let _ = sink.submit_command(PORKCHOP_COMPUTED, plot, Target::Auto);
+            });
+        }
+    });
+    let status_label = Label::new(|data: &PorkchopState, _env: &Env| data.status.clone());
+
+    Flex::column()
+        .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+        .with_child(porkchop_input_scope().lens(AppState::porkchop))
+        .with_default_spacer()
+        .with_child(
+            Flex::row()
+                .with_child(compute_button)
+                .with_default_spacer()
+                .with_child(status_label.lens(AppState::porkchop)),
+        )
+        .with_default_spacer()
+        .with_child(PorkchopPlot)
+        .with_default_spacer()
+        .with_child(porkchop_hover_label().lens(AppState::porkchop))
+        .padding(10.0)
+}
+
+pub fn build_orbit_view_ui() -> impl Widget<AppState> {
+    let export_button = Button::new("Export PNG...").on_click(|ctx, _data: &mut AppState, _env| {
+        ctx.submit_command(commands::SHOW_SAVE_PANEL.with(export_dialog_options()));
+    });
+    let reset_button = Button::new("Reset view")
+        .on_click(|_ctx, data: &mut AppState, _env| data.camera = Camera::default());
+
+    Flex::column()
+        .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(export_button)
+                .with_default_spacer()
+                .with_child(reset_button)
+                .with_default_spacer()
+                .with_child(file_label()),
+        )
+        .with_default_spacer()
+        .with_child(OrbitView3D::default())
+        .padding(10.0)
+}
+
+fn porkchop_input_scope() -> impl Widget<PorkchopState> {
+    let step = TextBox::new()
+        .with_formatter(FloatFormatter)
+        .validate_while_editing(true)
+        .update_data_while_editing(true)
+        .lens(PorkchopState::step);
+
+    Flex::column()
+        .cross_axis_alignment(druid::widget::CrossAxisAlignment::End)
+        .with_child(porkchop_window_row(
+            "departure",
+            PorkchopState::departure_planet,
+            PorkchopState::departure_start,
+            PorkchopState::departure_end,
+        ))
+        .with_default_spacer()
+        .with_child(porkchop_window_row(
+            "arrival",
+            PorkchopState::arrival_planet,
+            PorkchopState::arrival_start,
+            PorkchopState::arrival_end,
+        ))
+        .with_default_spacer()
+        .with_child(
+            Flex::row()
+                .cross_axis_alignment(druid::widget::CrossAxisAlignment::Baseline)
+                .with_child(Label::new("step (days): "))
+                .with_child(step),
+        )
+}
+
+fn porkchop_window_row(
+    name: &str,
+    planet: impl Lens<PorkchopState, String> + 'static,
+    start: impl Lens<PorkchopState, String> + 'static,
+    end: impl Lens<PorkchopState, String> + 'static,
+) -> impl Widget<PorkchopState> {
+    Flex::row()
+        .cross_axis_alignment(druid::widget::CrossAxisAlignment::Baseline)
+        .with_child(Label::new(name.to_string() + ": "))
+        .with_child(TextBox::new().lens(planet))
+        .with_child(Label::new(" from "))
+        .with_child(TextBox::new().lens(start))
+        .with_child(Label::new(" to "))
+        .with_child(TextBox::new().lens(end))
+}
+
+fn porkchop_hover_label() -> impl Widget<PorkchopState> {
+    Label::new(|data: &PorkchopState, _env: &Env| {
+        let node = match (&data.plot, data.hover) {
+            (Some(plot), Some(index)) => &plot.nodes[index],
+            _ => {
+                return "Hover over a cell to show its transfer, click to load it \
+                        into the Lambert solver"
+                    .to_string()
+            }
+        };
+        let dates = format!(
+            "departure {}, arrival {}, time of flight {:.1} days",
+            node.departure,
+            node.arrival,
+            node.time_of_flight().days()
+        );
+        match &node.outcome {
+            Ok(transfer) => format!(
+                "{}\nC3 = {:.3} km^2/s^2, arrival v-infinity = {:.3} km/s, total dv = {:.3} km/s",
+                dates,
+                transfer.c3(),
+                transfer.v_inf_arrival.mag(),
+                transfer.delta_v()
+            ),
+            Err(e) => format!("{}\n{}", dates, e),
+        }
+    })
+}
+
+// Heatmap of departure C3 over departure (x) and arrival (y) dates, with C3
+// contours in black and time-of-flight contours in white
+struct PorkchopPlot;
+
+impl PorkchopPlot {
+    fn area(size: Size) -> Rect {
+        let (left, top, right, bottom) = PORKCHOP_PLOT_MARGINS;
+        Rect::new(left, top, size.width - right, size.height - bottom)
+    }
+
+    fn node_at(plot: &Porkchop, area: Rect, pos: Point) -> Option<usize> {
+        if !area.contains(pos) {
+            return None;
+        }
+        let (columns, rows) = (plot.departures.len(), plot.arrivals.len());
+        let i = ((pos.x - area.x0) / area.width() * columns as f64) as usize;
+        let j = ((area.y1 - pos.y) / area.height() * rows as f64) as usize;
+        if i < columns && j < rows {
+            Some(i * rows + j)
+        } else {
+            None
+        }
+    }
+}
+
+impl Widget<AppState> for PorkchopPlot {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut AppState, _env: &Env) {
+        let plot = match &data.porkchop.plot {
+            Some(plot) => plot.clone(),
+            None => return,
+        };
+        let area = Self::area(ctx.size());
+        match event {
+            Event::MouseMove(mouse) => {
+                let hover = Self::node_at(&plot, area, mouse.pos);
+                if hover != data.porkchop.hover {
+                    data.porkchop.hover = hover;
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if let Some(index) = Self::node_at(&plot, area, mouse.pos) {
+                    data.load_porkchop_node(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &AppState,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &AppState, data: &AppState, _env: &Env) {
+        if !old_data.porkchop.plot.same(&data.porkchop.plot)
+            || old_data.porkchop.hover != data.porkchop.hover
+        {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &AppState,
+        _env: &Env,
+    ) -> Size {
+        bc.constrain(PORKCHOP_PLOT_SIZE)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &AppState, env: &Env) {
+        let area = Self::area(ctx.size());
+        ctx.fill(area, &Color::grey8(0x40));
+        let plot = match &data.porkchop.plot {
+            Some(plot) => plot,
+            None => {
+                let text = "Compute a porkchop plot to show it here";
+                draw_label(ctx, env, text, area.center(), (0.5, 0.5));
+                return;
+            }
+        };
+        let (columns, rows) = (plot.departures.len(), plot.arrivals.len());
+        let cell = Size::new(area.width() / columns as f64, area.height() / rows as f64);
+        let cell_rect = |index: usize| {
+            let (i, j) = (index / rows, index % rows);
+            Rect::from_origin_size(
+                (
+                    area.x0 + i as f64 * cell.width,
+                    area.y1 - (j + 1) as f64 * cell.height,
+                ),
+                cell,
+            )
+        };
+        // fractional grid indices to the centres of the cells
+        let point = |(i, j): (f64, f64)| {
+            Point::new(
+                area.x0 + (i + 0.5) * cell.width,
+                area.y1 - (j + 0.5) * cell.height,
+            )
+        };
+
+        let c3 = |node: &PorkchopNode| node.outcome.as_ref().ok().map(PorkchopTransfer::c3);
+        let mut c3_values: Vec<f64> = plot.nodes.iter().filter_map(c3).collect();
+        c3_values.sort_by(f64::total_cmp);
+        // the colour scale saturates at the upper quartile so that the window stands out
+        let (low, high) = match c3_values.first() {
+            Some(&low) => (low, c3_values[c3_values.len() * 3 / 4].max(low + 1.0)),
+            None => (0.0, 1.0),
+        };
+        for (index, node) in plot.nodes.iter().enumerate() {
+            let color = match c3(node) {
+                Some(c3) => heat_color((c3 - low) / (high - low)),
+                None => Color::grey8(0x60),
+            };
+            ctx.fill(cell_rect(index), &color);
+        }
+
+        let c3_step = nice_step((high - low) / 6.0);
+        let mut level = (low / c3_step).ceil() * c3_step;
+        while level <= high {
+            for [a, b] in plot.contour(c3, level) {
+                ctx.stroke(Line::new(point(a), point(b)), &Color::BLACK, 1.0);
+            }
+            level += c3_step;
+        }
+        let tof = |node: &PorkchopNode| Some(node.time_of_flight().days());
+        let tofs = plot.nodes.iter().map(|node| node.time_of_flight().days());
+        let tof_min = tofs.clone().fold(f64::INFINITY, f64::min).max(0.0);
+        let tof_max = tofs.fold(f64::NEG_INFINITY, f64::max);
+        let tof_step = nice_step((tof_max - tof_min) / 6.0);
+        let mut level = (tof_min / tof_step).ceil() * tof_step;
+        while level <= tof_max {
+            for [a, b] in plot.contour(tof, level) {
+                ctx.stroke(Line::new(point(a), point(b)), &Color::WHITE, 1.0);
+            }
+            level += tof_step;
+        }
+
+        if let Some(index) = data.porkchop.hover {
+            ctx.stroke(cell_rect(index), &Color::WHITE, 2.0);
+        }
+
+        let date = |epoch: &Epoch| {
+            let dt = epoch.datetime(TimeScale::Utc);
+            format!("{:04}-{:02}-{:02}", dt.year, dt.month, dt.day)
+        };
+        let (first_departure, last_departure) =
+            (&plot.departures[0], &plot.departures[columns - 1]);
+        let (first_arrival, last_arrival) = (&plot.arrivals[0], &plot.arrivals[rows - 1]);
+        let below = area.y1 + 4.0;
+        draw_label(
+            ctx,
+            env,
+            &date(first_departure),
+            Point::new(area.x0, below),
+            (0.0, 0.0),
+        );
+        draw_label(
+            ctx,
+            env,
+            &date(last_departure),
+            Point::new(area.x1, below),
+            (1.0, 0.0),
+        );
+        let departure = format!("departure from {}", plot.departure_planet.body().name);
+        draw_label(
+            ctx,
+            env,
+            &departure,
+            Point::new(area.center().x, below),
+            (0.5, 0.0),
+        );
+        let left = area.x0 - 4.0;
+        draw_label(
+            ctx,
+            env,
+            &date(first_arrival),
+            Point::new(left, area.y1),
+            (1.0, 1.0),
+        );
+        draw_label(
+            ctx,
+            env,
+            &date(last_arrival),
+            Point::new(left, area.y0),
+            (1.0, 0.0),
+        );
+        let arrival = format!("arrival at\n{}", plot.arrival_planet.body().name);
+        draw_label(
+            ctx,
+            env,
+            &arrival,
+            Point::new(left, area.center().y),
+            (1.0, 0.5),
+        );
+        let legend = format!(
+            "C3 {:.1} to {:.1} km^2/s^2, black contours every {} km^2/s^2, \
+             white contours every {} days of flight",
+            low, high, c3_step, tof_step
+        );
+        draw_label(
+            ctx,
+            env,
+            &legend,
+            Point::new(area.x0, below + 24.0),
+            (0.0, 0.0),
+        );
+    }
+}
+
+// Transfer plane seen from the orbit normal with periapsis to the right, solved
+// again on every change of the inputs. Drag to pan, scroll to zoom about the
+// pointer and double-click to reset the view.
+struct OrbitPlot {
+    zoom: f64,
+    pan: Vec2,
+    // last pointer position while dragging
+    drag: Option<Point>,
+}
+
+impl OrbitPlot {
+    fn new() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: Vec2::ZERO,
+            drag: None,
+        }
+    }
+}
+
+impl Widget<LambertSolver> for OrbitPlot {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut LambertSolver, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() && mouse.count == 2 => {
+                self.zoom = 1.0;
+                self.pan = Vec2::ZERO;
+                ctx.request_paint();
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                self.drag = Some(mouse.pos);
+                ctx.set_active(true);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(last) = self.drag {
+                    self.pan += mouse.pos - last;
+                    self.drag = Some(mouse.pos);
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                self.drag = None;
+                ctx.set_active(false);
+            }
+            Event::Wheel(mouse) => {
+                // keeps the point under the pointer in place
+                let factor = (-mouse.wheel_delta.y / 400.0).exp();
+                let center = ctx.size().to_rect().center();
+                self.pan = (mouse.pos - center) * (1.0 - factor) + self.pan * factor;
+                self.zoom *= factor;
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &LambertSolver,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &LambertSolver,
+        data: &LambertSolver,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &LambertSolver,
+        _env: &Env,
+    ) -> Size {
+        bc.constrain(ORBIT_PLOT_SIZE)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, ls: &LambertSolver, env: &Env) {
+        let bounds = ctx.size().to_rect();
+        ctx.fill(bounds, &Color::grey8(0x20));
+        let (r1, r2, mu) = (ls.get_r1(), ls.get_r2(), ls.get_mu());
+        let solution = ls.get_solution();
+        let orbit = solution.map(|s| Orbit::from_rv(r2, s.v2, mu));
+
+        // plane of the transfer orbit, or of r1 and r2 without a solution
+        let (i_p, i_q) = match &orbit {
+            Some(orbit) => orbit.perifocal_basis(),
+            None => plane_basis(r1, r2),
+        };
+        let plane = |r: Vector3D| (r * i_p, r * i_q);
+        let (x1, y1) = plane(r1);
+        let (x2, y2) = plane(r2);
+
+        // r1, r2 and the central body fit the view at zoom 1, closed orbits too
+        // unless the apoapsis is far out
+        let r_max = r1.mag().max(r2.mag()).max(ls.body_radius);
+        let apoapsis = orbit.as_ref().map_or(0.0, Orbit::apoapsis_radius);
+        let extent = if apoapsis < 4.0 * r_max {
+            apoapsis.max(r_max) * 1.15
+        } else {
+            r_max * 1.15
+        };
+        let scale = if extent > 0.0 {
+            0.5 * bounds.width().min(bounds.height()) / extent * self.zoom
+        } else {
+            self.zoom
+        };
+        let origin = bounds.center() + self.pan;
+        let point = |(x, y): (f64, f64)| Point::new(origin.x + x * scale, origin.y - y * scale);
+
+        ctx.with_save(|ctx| {
+            ctx.clip(bounds);
+            let body = Circle::new(origin, (ls.body_radius * scale).max(3.0));
+            ctx.fill(body, &Color::rgb8(0x30, 0x60, 0xa0));
+            let radius_color = Color::grey8(0xa0);
+            ctx.stroke(Line::new(origin, point((x1, y1))), &radius_color, 1.0);
+            ctx.stroke(Line::new(origin, point((x2, y2))), &radius_color, 1.0);
+
+            if let (Some(solution), Some(orbit)) = (solution, &orbit) {
+                let polyline = |nu1: f64, nu2: f64| {
+                    let mut path = BezPath::new();
+                    for k in 0..=ORBIT_PLOT_SAMPLES {
+                        let nu = nu1 + (nu2 - nu1) * k as f64 / ORBIT_PLOT_SAMPLES as f64;
+                        let r = orbit.radius_at(nu);
+                        let p = point((r * nu.cos(), r * nu.sin()));
+                        if k == 0 {
+                            path.move_to(p);
+                        } else {
+                            path.line_to(p);
+                        }
+                    }
+                    path
+                };
+                let elliptic = Conic::from_ecc(orbit.ecc) == Conic::Elliptic;
+
+                // full conic, open orbits out to 20 view extents from the focus
+                let nu_max = if elliptic {
+                    PI
+                } else {
+                    ((orbit.p / (20.0 * extent) - 1.0) / orbit.ecc)
+                        .clamp(-1.0, 1.0)
+                        .acos()
+                };
+                ctx.stroke(polyline(-nu_max, nu_max), &Color::grey8(0x70), 1.0);
+
+                // transfer arc in the direction of motion, towards increasing true anomaly
+                let nu1 = y1.atan2(x1);
+                let nu2 = y2.atan2(x2);
+                let nu2 = if elliptic {
+                    nu1 + (nu2 - nu1).rem_euclid(2.0 * PI)
+                } else {
+                    nu2
+                };
+                let arc_color = Color::rgb8(0xf0, 0xa0, 0x20);
+                ctx.stroke(polyline(nu1, nu2), &arc_color, 2.5);
+
+                // arrow head along v2 at the arrival point
+                let (vx, vy) = plane(solution.v2);
+                let tip = point((x2, y2));
+                let direction = Vec2::new(vx, -vy).normalize() * 10.0;
+                let side = Vec2::new(-direction.y, direction.x) * 0.5;
+                let mut arrow = BezPath::new();
+                arrow.move_to(tip + direction);
+                arrow.line_to(tip + side);
+                arrow.line_to(tip - side);
+                arrow.close_path();
+                ctx.fill(arrow, &arc_color);
+            }
+
+            let offset = Vec2::new(4.0, -4.0);
+            draw_label(ctx, env, "r1", point((x1, y1)) + offset, (0.0, 1.0));
+            draw_label(ctx, env, "r2", point((x2, y2)) + offset, (0.0, 1.0));
+        });
+
+        let info = match (&ls.status, &orbit) {
+            (LambertSolverStatus::Solved(_), Some(orbit)) => {
+                let conic = match Conic::from_ecc(orbit.ecc) {
+                    Conic::Elliptic => "elliptic",
+                    Conic::Parabolic => "parabolic",
+                    Conic::Hyperbolic => "hyperbolic",
+                };
+                format!("{} transfer, e = {:.4}", conic, orbit.ecc)
+            }
+            (LambertSolverStatus::Failed(e), _) => e.to_string(),
+            _ => String::new(),
+        };
+        draw_label(ctx, env, &info, Point::new(6.0, 4.0), (0.0, 0.0));
+    }
+}
+
+// Central body, inertial axes, departure and arrival orbits and the transfer arc
+// drawn by the software renderer. Drag to rotate the camera about the origin,
+// scroll to zoom and double-click to reset the view.
+#[derive(Default)]
+struct OrbitView3D {
+    // last pointer position while dragging
+    drag: Option<Point>,
+}
+
+impl Widget<AppState> for OrbitView3D {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut AppState, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() && mouse.count == 2 => {
+                data.camera = Camera::default();
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                self.drag = Some(mouse.pos);
+                ctx.set_active(true);
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                // half a turn across the width of the view
+                if let Some(last) = self.drag {
+                    let delta = (mouse.pos - last) * PI / ctx.size().width.max(1.0);
+                    data.camera.rotate(-delta.x, delta.y);
+                    self.drag = Some(mouse.pos);
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                self.drag = None;
+                ctx.set_active(false);
+            }
+            Event::Wheel(mouse) => {
+                data.camera.zoom *= (-mouse.wheel_delta.y / 400.0).exp();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &AppState,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &AppState, data: &AppState, _env: &Env) {
+        if !old_data.lambert_problem.same(&data.lambert_problem)
+            || !old_data.camera.same(&data.camera)
+        {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &AppState,
+        _env: &Env,
+    ) -> Size {
+        bc.constrain(ORBIT_VIEW_SIZE)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &AppState, env: &Env) {
+        let size = ctx.size();
+        let (width, height) = (size.width as usize, size.height as usize);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let scene = render::transfer_scene(&data.lambert_problem);
+        let image = scene.render(&data.camera, width, height);
+        if let Ok(image) = ctx.make_image(width, height, &image.pixels, ImageFormat::Rgb) {
+            let rect = Size::new(width as f64, height as f64).to_rect();
+            ctx.draw_image(&image, rect, InterpolationMode::Bilinear);
+        }
+
+        for (name, tip) in ["X", "Y", "Z"].iter().zip(scene.axis_tips().iter()) {
+            if let Some((x, y)) = scene.project(&data.camera, width, height, *tip) {
+                draw_label(ctx, env, name, Point::new(x, y), (0.5, 0.5));
+            }
+        }
+        let info = match &data.lambert_problem.status {
+            LambertSolverStatus::Solved(_) => {
+                "transfer arc in orange, departure orbit in cyan, arrival orbit in pink".to_string()
+            }
+            LambertSolverStatus::Failed(e) => e.to_string(),
+            LambertSolverStatus::Initialized => String::new(),
+        };
+        draw_label(ctx, env, &info, Point::new(6.0, 4.0), (0.0, 0.0));
+    }
+}
+
+fn plane_basis(r1: Vector3D, r2: Vector3D) -> (Vector3D, Vector3D) {
+    // x along r1 and y towards r2 in their plane, falling back to the XY plane
+    let normal = r1.cross(r2);
+    if r1.mag() == 0.0 || normal.mag() == 0.0 {
+        return (Vector3D::new(1.0, 0.0, 0.0), Vector3D::new(0.0, 1.0, 0.0));
+    }
+    let i_p = r1.norm();
+    (i_p, normal.cross(i_p).norm())
+}
+
+fn draw_label(ctx: &mut PaintCtx, env: &Env, text: &str, anchor: Point, align: (f64, f64)) {
+    // align is the fraction of the text size left of and above the anchor
+    let layout = ctx
+        .text()
+        .new_text_layout(text.to_string())
+        .text_color(env.get(theme::LABEL_COLOR))
+        .build();
+    if let Ok(layout) = layout {
+        let size = layout.size();
+        let origin = (
+            anchor.x - align.0 * size.width,
+            anchor.y - align.1 * size.height,
+        );
+        ctx.draw_text(&layout, origin);
+    }
+}
+
+fn heat_color(t: f64) -> Color {
+    // dark blue through green and yellow to red as t goes from 0 to 1
+    let stops = [
+        (0.10, 0.05, 0.45),
+        (0.10, 0.50, 0.80),
+        (0.35, 0.75, 0.25),
+        (0.95, 0.85, 0.20),
+        (0.80, 0.15, 0.10),
+    ];
+    let x = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+    let i = (x.floor() as usize).min(stops.len() - 2);
+    let s = x - i as f64;
+    let (a, b) = (stops[i], stops[i + 1]);
+    Color::rgb(
+        a.0 + s * (b.0 - a.0),
+        a.1 + s * (b.1 - a.1),
+        a.2 + s * (b.2 - a.2),
+    )
+}
+
+fn nice_step(range: f64) -> f64 {
+    // 1, 2 or 5 times a power of ten close to range
+    if !(range.is_finite() && range > 0.0) {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(range.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= range)
+        .unwrap_or(10.0 * magnitude)
+}
+
+fn file_label() -> impl Widget<AppState> {
+    Label::new(|data: &AppState, _env: &Env| {
+        let file = match &data.file {
+            Some(path) => path.display().to_string(),
+            None => "unsaved scenario".to_string(),
+        };
+        if data.file_status.is_empty() {
+            format!("File: {}", file)
+        } else {
+            format!("File: {}\n{}", file, data.file_status)
+        }
+    })
+}
+
+fn lambert_scope() -> impl Widget<LambertSolver> {
     let solve_button = Button::new("Solve")
         .on_click(|_ctx, ls: &mut LambertSolver, _env| ls.recalculate_solution());
 
@@ -20,15 +953,34 @@ pub fn build_ui() -> impl Widget<LambertSolver> {
                 .with_default_spacer()
                 .with_child(r_input_scope("r2 (km)", &LambertSolver::r2_v))
                 .with_default_spacer()
+                .with_child(v_input_scope(
+                    "spacecraft velocity at r1 (km/s)",
+                    LambertSolver::v_dep,
+                ))
+                .with_default_spacer()
+                .with_child(v_input_scope(
+                    "target velocity at r2 (km/s)",
+                    LambertSolver::v_arr,
+                ))
+                .with_default_spacer()
                 .with_child(float_value_input_scope(
                     "time-of-flight (s)",
                     LambertSolver::t,
                 ))
                 .with_default_spacer()
                 .with_child(float_value_input_scope(
-                    "gravitational parameter (km^3 / s)",
+                    "gravitational parameter (km^3 / s^2)",
                     LambertSolver::mu,
-                )),
+                ))
+                .with_default_spacer()
+                .with_child(float_value_input_scope(
+                    "body radius (km)",
+                    LambertSolver::body_radius,
+                ))
+                .with_default_spacer()
+                .with_child(direction_input_scope())
+                .with_default_spacer()
+                .with_child(revolutions_input_scope()),
         )
         .with_default_spacer()
         .with_child(
@@ -39,19 +991,45 @@ pub fn build_ui() -> impl Widget<LambertSolver> {
         )
         .with_default_spacer()
         .with_child(
-            Flex::column()
+            Flex::row()
                 .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
-                .with_child(v1_output_scope())
+                .with_child(
+                    Flex::column()
+                        .cross_axis_alignment(druid::widget::CrossAxisAlignment::Start)
+                        .with_child(v1_output_scope())
+                        .with_default_spacer()
+                        .with_child(v2_output_scope())
+                        .with_default_spacer()
+                        .with_child(delta_v_output_scope())
+                        .with_default_spacer()
+                        .with_child(orbit_output_scope()),
+                )
                 .with_default_spacer()
-                .with_child(v2_output_scope()),
+                .with_child(OrbitPlot::new()),
         )
-        .center()
+        .controller(AutoSolve)
 }
 
 fn r_input_scope<L: Lens<LambertSolver, Vector3D> + 'static + Copy>(
     name: &str,
     lens: &L,
 ) -> impl Widget<LambertSolver> {
+    Flex::row()
+        .cross_axis_alignment(druid::widget::CrossAxisAlignment::Baseline)
+        .with_child(Label::new(name.to_string() + ":   "))
+        .with_default_spacer()
+        .with_child(float_value_input_scope("x", lens.then(Vector3D::x)))
+        .with_child(float_value_input_scope("y", lens.then(Vector3D::y)))
+        .with_child(float_value_input_scope("z", lens.then(Vector3D::z)))
+        .with_default_spacer()
+        .with_child(float_value_input_scope("altitude", altitude_lens(*lens)))
+}
+
+fn v_input_scope<L: Lens<LambertSolver, Vector3D> + 'static + Copy>(
+    name: &str,
+    lens: L,
+) -> impl Widget<LambertSolver> {
+    // velocities on the departure and arrival orbits, zero to depart from rest
     Flex::row()
         .cross_axis_alignment(druid::widget::CrossAxisAlignment::Baseline)
         .with_child(Label::new(name.to_string() + ":   "))
@@ -61,6 +1039,24 @@ fn r_input_scope<L: Lens<LambertSolver, Vector3D> + 'static + Copy>(
         .with_child(float_value_input_scope("z", lens.then(Vector3D::z)))
 }
 
+fn altitude_lens<L: Lens<LambertSolver, Vector3D> + Copy>(
+    lens: L,
+) -> impl Lens<LambertSolver, f64> {
+    // height above the body radius, editing it keeps the direction of the vector
+    lens::Map::new(
+        move |ls: &LambertSolver| lens.get(ls).mag() - ls.body_radius,
+        move |ls: &mut LambertSolver, altitude: f64| {
+            let r = lens.get(ls);
+            let direction = if r.mag() > 0.0 {
+                r.norm()
+            } else {
+                Vector3D::new(1.0, 0.0, 0.0)
+            };
+            lens.put(ls, (altitude + ls.body_radius) * direction);
+        },
+    )
+}
+
 fn float_value_input_scope<L: Lens<LambertSolver, f64>>(
     name: &str,
     lens: L,
@@ -79,20 +1075,58 @@ fn float_value_input_scope<L: Lens<LambertSolver, f64>>(
         .lens(lens)
 }
 
+fn direction_input_scope() -> impl Widget<LambertSolver> {
+    Flex::row()
+        .with_child(Label::new("direction: "))
+        .with_child(Radio::new("prograde", TransferDirection::Prograde))
+        .with_default_spacer()
+        .with_child(Radio::new("retrograde", TransferDirection::Retrograde))
+        .with_default_spacer()
+        .with_child(Radio::new("short way", TransferDirection::ShortWay))
+        .with_default_spacer()
+        .with_child(Radio::new("long way", TransferDirection::LongWay))
+        .lens(LambertSolver::options.then(LambertOptions::direction))
+}
+
+fn revolutions_input_scope() -> impl Widget<LambertSolver> {
+    // full revolutions and the branch of the multi-revolution solution pair
+    let revolutions = LambertSolver::options
+        .then(LambertOptions::revolutions)
+        .map(
+            |&n: &u32| f64::from(n),
+            |n: &mut u32, value: f64| *n = value.max(0.0).round() as u32,
+        );
+    Flex::row()
+        .cross_axis_alignment(druid::widget::CrossAxisAlignment::Baseline)
+        .with_child(float_value_input_scope("revolutions", revolutions))
+        .with_default_spacer()
+        .with_child(
+            Flex::row()
+                .with_child(Label::new("branch: "))
+                .with_child(Radio::new("left", LambertBranch::Left))
+                .with_default_spacer()
+                .with_child(Radio::new("right", LambertBranch::Right))
+                .lens(LambertSolver::options.then(LambertOptions::branch)),
+        )
+}
+
 fn converged_label() -> impl Widget<LambertSolver> {
     let converged_label = Label::new(|ls: &LambertSolver, _env: &Env| {
         format!(
             "Status: {}",
-            match ls.status {
-                LambertSolverStatus::Converged => "Solution converged",
-                LambertSolverStatus::InvalidInput => "Invalid input",
-                LambertSolverStatus::NotConverged => "Solution did not converge",
-                LambertSolverStatus::CollinearVectors =>
-                    "cannot determine orbit, collinear radius-vectors",
-                LambertSolverStatus::MultiRevolution =>
-                    "time-of-flight is too big, multi-revolution is not supported",
-                LambertSolverStatus::Initialized => "Initializing",
-                LambertSolverStatus::InternalError => "Internal error",
+            match &ls.status {
+                LambertSolverStatus::Solved(s) => {
+                    let converged = format!(
+                        "Solution converged in {} iterations, time-of-flight residual {:.3e} s",
+                        s.iterations, s.residual
+                    );
+                    match s.warning {
+                        Some(warning) => format!("{}\nWarning: {}", converged, warning),
+                        None => converged,
+                    }
+                }
+                LambertSolverStatus::Failed(e) => e.to_string(),
+                LambertSolverStatus::Initialized => "Initializing".to_string(),
             }
         )
     });
@@ -126,3 +1160,68 @@ fn v2_output_scope() -> impl Widget<LambertSolver> {
             format!("|v2| = {:.4} km/s", v2.mag())
         }))
 }
+
+fn delta_v_output_scope() -> impl Widget<LambertSolver> {
+    // impulses against the spacecraft and target velocities
+    Label::new(|ls: &LambertSolver, _env: &Env| {
+        let dv = match ls.get_delta_v() {
+            Some(dv) => dv,
+            None => return "Delta-v: no solution".to_string(),
+        };
+        let (d, a, v_inf) = (dv.departure, dv.arrival, dv.v_inf());
+        format!(
+            "Delta-v:\n\
+             departure = ({:.4}, {:.4}, {:.4}), |dv1| = {:.4} km/s\n\
+             arrival = ({:.4}, {:.4}, {:.4}), |dv2| = {:.4} km/s\n\
+             rendezvous total = {:.4} km/s\n\
+             flyby v-infinity = ({:.4}, {:.4}, {:.4}), |v-inf| = {:.4} km/s",
+            d.x,
+            d.y,
+            d.z,
+            d.mag(),
+            a.x,
+            a.y,
+            a.z,
+            a.mag(),
+            dv.total(),
+            v_inf.x,
+            v_inf.y,
+            v_inf.z,
+            v_inf.mag(),
+        )
+    })
+}
+
+fn orbit_output_scope() -> impl Widget<LambertSolver> {
+    // transfer orbit at arrival
+    Label::new(|ls: &LambertSolver, _env: &Env| {
+        if ls.get_solution().is_none() {
+            return "Transfer orbit: no solution".to_string();
+        }
+        let orbit = ls.get_orbit();
+        format!(
+            "Transfer orbit:\n\
+             a = {:.4} km, e = {:.6}, i = {:.4} deg\n\
+             period = {:.4} s, mean motion = {:.6e} rad/s\n\
+             specific energy = {:.4} km^2/s^2, specific angular momentum = {:.4} km^2/s\n\
+             periapsis radius = {:.4} km, altitude = {:.4} km\n\
+             apoapsis radius = {:.4} km, altitude = {:.4} km\n\
+             flight-path angle at r2 = {:.4} deg\n\
+             time since periapsis = {:.4} s, time to periapsis = {:.4} s",
+            orbit.a,
+            orbit.ecc,
+            orbit.inc.to_degrees(),
+            orbit.period(),
+            orbit.mean_motion(),
+            orbit.specific_energy(),
+            orbit.specific_angular_momentum(),
+            orbit.periapsis_radius(),
+            orbit.periapsis_altitude(ls.body_radius),
+            orbit.apoapsis_radius(),
+            orbit.apoapsis_altitude(ls.body_radius),
+            orbit.flight_path_angle().to_degrees(),
+            orbit.time_since_periapsis(),
+            orbit.time_to_periapsis(),
+        )
+    })
+}